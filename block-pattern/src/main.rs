@@ -1,17 +1,363 @@
+use std::fmt;
+use std::time::Duration;
+
+use anyhow::Context;
+use common::diff::{describe, Changes, Diffable, FieldChange};
+use common::optional_builder;
 use serde::{Deserialize};
-use toml;
-use anyhow;
 
-#[derive(Debug, Deserialize)]
+/// The newest configuration layout this program understands.
+const CURRENT_CONFIG_VERSION: u32 = 2;
+
+// `with_should_open`, to adjust a deserialized `Config` in code: `cfg.with_should_open(true)`.
+// The macro is shared with the builder crate.
+optional_builder! {
+    #[derive(Debug, Deserialize)]
+    #[allow(dead_code)]
+    struct Config {
+        /// Files written before versioning existed have no `version` key, those are version 1.
+        #[serde(default = "default_config_version")]
+        version: u32,
+        title: ConfigTitle,
+        width: i32,
+        height: i32,
+        should_open: Option<bool>,
+        tail: bool,
+        /// The `[window]` table and everything nested in it. All of it is optional.
+        #[serde(default)]
+        window: WindowOptions,
+        /// `Info` when the key is missing.
+        #[serde(default)]
+        log_level: LogLevel,
+    }
+}
+
+/// How much the program should log, from everything (`Trace`) to only the errors.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
 #[allow(dead_code)]
-struct Config {
-    title: String,
-    width: i32,
-    height: i32,
-    should_open: Option<bool>,
-    tail: bool
+enum LogLevel {
+    Trace,
+    Debug,
+    #[default]
+    Info,
+    Warn,
+    Error,
+}
+
+impl std::str::FromStr for LogLevel {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "trace" => Ok(LogLevel::Trace),
+            "debug" => Ok(LogLevel::Debug),
+            "info" => Ok(LogLevel::Info),
+            "warn" => Ok(LogLevel::Warn),
+            "error" => Ok(LogLevel::Error),
+            _ => Err(format!("unknown log level {:?}, expected one of trace, debug, info, warn, error", s)),
+        }
+    }
+}
+
+/// Written by hand instead of derived to control the error: serde's own would list the variant
+/// names, we want the lowercase strings a user actually writes in the TOML.
+impl<'de> Deserialize<'de> for LogLevel {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let level = String::deserialize(deserializer)?;
+        level.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+impl fmt::Display for LogLevel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let level = match self {
+            LogLevel::Trace => "trace",
+            LogLevel::Debug => "debug",
+            LogLevel::Info => "info",
+            LogLevel::Warn => "warn",
+            LogLevel::Error => "error",
+        };
+        write!(f, "{}", level)
+    }
+}
+
+optional_builder! {
+    #[derive(Debug, Default, Deserialize)]
+    #[allow(dead_code)]
+    struct WindowOptions {
+        /// `[window.border]`, `None` when the table is missing.
+        border: Option<Border>,
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[allow(dead_code)]
+struct Border {
+    width: u8,
+    color: String,
+}
+
+
+/// Why a `Config` value was rejected.
+#[derive(Debug, PartialEq)]
+enum ConfigError {
+    EmptyTitle,
+    TitleTooLong(usize),
+    /// The title is not in the list of allowed ones.
+    TitleNotAllowed(String),
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigError::EmptyTitle => write!(f, "the title must not be empty"),
+            ConfigError::TitleTooLong(len) => {
+                write!(f, "the title is {} characters long, the maximum is {}", len, ConfigTitle::MAX_LEN)
+            },
+            ConfigError::TitleNotAllowed(title) => write!(f, "the title {:?} is not allowed", title),
+        }
+    }
 }
 
+impl std::error::Error for ConfigError {}
+
+/// A title that is known to be valid: not empty and at most `MAX_LEN` characters.
+/// Serde goes through `TryFrom<String>`, so an invalid title fails the whole deserialization.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(try_from = "String")]
+struct ConfigTitle(String);
+
+impl ConfigTitle {
+    const MAX_LEN: usize = 100;
+
+    fn new(s: impl Into<String>) -> Result<Self, ConfigError> {
+        let s = s.into();
+        let len = s.chars().count();
+        if len == 0 {
+            return Err(ConfigError::EmptyTitle);
+        }
+        if len > Self::MAX_LEN {
+            return Err(ConfigError::TitleTooLong(len));
+        }
+        Ok(Self(s))
+    }
+
+    #[allow(dead_code)]
+    fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl TryFrom<String> for ConfigTitle {
+    type Error = ConfigError;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        ConfigTitle::new(s)
+    }
+}
+
+impl fmt::Display for ConfigTitle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+fn default_config_version() -> u32 { 1 }
+
+/// Expands `${NAME}` with whatever `lookup` returns for `NAME`. `$$` is a literal `$`, and a `$`
+/// not followed by `{` is left alone. Values are not expanded again, so no nesting.
+fn expand_placeholders(s: &str, lookup: impl Fn(&str) -> Option<String>) -> anyhow::Result<String> {
+    let mut out = String::with_capacity(s.len());
+    let mut rest = s;
+
+    while let Some(start) = rest.find('$') {
+        out.push_str(&rest[..start]);
+        rest = &rest[start..];
+
+        if let Some(after) = rest.strip_prefix("$$") {
+            out.push('$');
+            rest = after;
+        } else if let Some(after) = rest.strip_prefix("${") {
+            let end = after.find('}').with_context(|| format!("unterminated placeholder in {:?}", s))?;
+            let name = &after[..end];
+            if name.is_empty() || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+                anyhow::bail!("invalid variable name {:?} in {:?}", name, s);
+            }
+            let value = lookup(name).with_context(|| format!("environment variable {} is not defined", name))?;
+            out.push_str(&value);
+            rest = &after[end + 1..];
+        } else {
+            out.push('$');
+            rest = &rest[1..];
+        }
+    }
+    out.push_str(rest);
+
+    Ok(out)
+}
+
+impl Config {
+    /// Upgrades an older configuration to `CURRENT_CONFIG_VERSION`, one version at a time.
+    /// A version newer than the one we know about can't be understood, so it is an error, and so
+    /// is version 0, which never existed.
+    fn migrate(&mut self) -> anyhow::Result<()> {
+        if self.version > CURRENT_CONFIG_VERSION {
+            anyhow::bail!(
+                "config version {} is newer than the supported version {}",
+                self.version,
+                CURRENT_CONFIG_VERSION
+            );
+        }
+        if self.version == 0 {
+            anyhow::bail!("config version 0 does not exist, versions start at 1");
+        }
+
+        while self.version < CURRENT_CONFIG_VERSION {
+            match self.version {
+                // Version 2 made `should_open` explicit. Older files did not open by default.
+                1 => { self.should_open.get_or_insert(false); },
+                // The version comes from the user's file, a gap in the migrations must not panic
+                version => anyhow::bail!("config version {} has no migration", version),
+            }
+            self.version += 1;
+        }
+
+        Ok(())
+    }
+
+    /// Replaces every `${VAR}` in the title with the value of the environment variable `VAR`.
+    /// An undefined variable is an error, and so is the expanded title if it's no longer valid.
+    #[allow(dead_code)]
+    fn expand_env(&mut self) -> anyhow::Result<()> {
+        self.expand_vars(|name| std::env::var(name).ok())
+    }
+
+    /// `expand_env` with the lookup pulled out, like `from_vars`.
+    fn expand_vars(&mut self, lookup: impl Fn(&str) -> Option<String>) -> anyhow::Result<()> {
+        let expanded = expand_placeholders(self.title.as_str(), lookup)?;
+        self.title = ConfigTitle::new(expanded).context("expanding the title")?;
+        Ok(())
+    }
+
+    /// `width * height` in `i32` overflows for big enough values. Widening both sides to `i64`
+    /// first gives room for any pair of `i32`, `checked_mul` keeps us honest anyway.
+    fn area(&self) -> Option<i64> {
+        i64::from(self.width).checked_mul(i64::from(self.height))
+    }
+
+    /// For restricted deployments: the title must be one of `allowed`, ignoring case.
+    #[allow(dead_code)]
+    fn validate_title_in(&self, allowed: &[&str]) -> Result<(), ConfigError> {
+        let title = self.title.as_str().to_lowercase();
+        if allowed.iter().any(|candidate| candidate.to_lowercase() == title) {
+            Ok(())
+        } else {
+            Err(ConfigError::TitleNotAllowed(self.title.to_string()))
+        }
+    }
+
+    /// Layers `other` on top of `self`, like an environment config over a base one. A field of
+    /// `other` only wins when it says something: dimensions that are not 0, a `tail` of `true`,
+    /// `Option`s that are `Some`. The title is never empty, so it always wins. `version` stays,
+    /// both sides are expected to be migrated already.
+    #[allow(dead_code)]
+    fn overlay(&mut self, other: &Config) {
+        self.title = other.title.clone();
+        if other.width != 0 {
+            self.width = other.width;
+        }
+        if other.height != 0 {
+            self.height = other.height;
+        }
+        if other.should_open.is_some() {
+            self.should_open = other.should_open;
+        }
+        self.tail |= other.tail;
+        if other.log_level != LogLevel::default() {
+            self.log_level = other.log_level;
+        }
+        if other.window.border.is_some() {
+            self.window.border = other.window.border.clone();
+        }
+    }
+
+    /// Forces both dimensions into `[min, max]`, for configs that come from somewhere we don't
+    /// trust. Like `Ord::clamp`, it panics when `min > max`.
+    #[allow(dead_code)]
+    fn clamp_dimensions(&mut self, min: i32, max: i32) {
+        self.width = self.width.clamp(min, max);
+        self.height = self.height.clamp(min, max);
+    }
+
+    /// `width / height`, `None` when there is no height to divide by.
+    #[allow(dead_code)]
+    fn aspect_ratio(&self) -> Option<f64> {
+        (self.height != 0).then(|| f64::from(self.width) / f64::from(self.height))
+    }
+
+    /// Wider than tall. A square is not landscape.
+    #[allow(dead_code)]
+    fn is_landscape(&self) -> Option<bool> {
+        self.aspect_ratio().map(|ratio| ratio > 1.0)
+    }
+}
+
+// `Diffable` comes from the `common` crate, so audit code takes a `Config` as well as a `Person`.
+impl Diffable for Config {
+    fn diff(&self, other: &Config) -> Vec<FieldChange> {
+        let border = |cfg: &Config| describe(&cfg.window.border.as_ref().map(|b| format!("{} {}", b.width, b.color)));
+
+        let mut changes = Changes::default();
+        changes.compare("version", self.version.to_string(), other.version.to_string());
+        changes.compare("title", self.title.to_string(), other.title.to_string());
+        changes.compare("width", self.width.to_string(), other.width.to_string());
+        changes.compare("height", self.height.to_string(), other.height.to_string());
+        changes.compare("should_open", describe(&self.should_open), describe(&other.should_open));
+        changes.compare("tail", self.tail.to_string(), other.tail.to_string());
+        changes.compare("window.border", border(self), border(other));
+        changes.compare("log_level", self.log_level.to_string(), other.log_level.to_string());
+        changes.into_vec()
+    }
+}
+
+/// A single rule a `Config` must follow. Implement it to plug new checks into `validate_with`.
+trait ConfigValidator {
+    fn validate(&self, cfg: &Config) -> Result<(), String>;
+}
+
+/// Both dimensions must be positive.
+#[allow(dead_code)]
+struct PositiveDimensions;
+
+impl ConfigValidator for PositiveDimensions {
+    fn validate(&self, cfg: &Config) -> Result<(), String> {
+        if cfg.width <= 0 || cfg.height <= 0 {
+            return Err(format!("dimensions must be positive, got {}x{}", cfg.width, cfg.height));
+        }
+        Ok(())
+    }
+}
+
+/// The title can't be empty or only whitespace.
+#[allow(dead_code)]
+struct NonEmptyTitle;
+
+impl ConfigValidator for NonEmptyTitle {
+    fn validate(&self, cfg: &Config) -> Result<(), String> {
+        if cfg.title.as_str().trim().is_empty() {
+            return Err(String::from("title must not be empty"));
+        }
+        Ok(())
+    }
+}
+
+impl Config {
+    /// Runs the validators in order and stops at the first one that fails.
+    #[allow(dead_code)]
+    fn validate_with(&self, validators: &[&dyn ConfigValidator]) -> Result<(), String> {
+        validators.iter().try_for_each(|validator| validator.validate(self))
+    }
+}
 
 #[allow(unused_doc_comments)]
 /// The "normal" way would be to do it in sequence.
@@ -24,7 +370,10 @@ fn normal_get_config(path: &str) -> anyhow::Result<()> {
     let config_str = String::from_utf8(raw_config)?;
 
     // Then we deserialize it
-    let config: Config = toml::from_str(&config_str)?;
+    let mut config: Config = toml::from_str(&config_str)?;
+
+    // Then we bring it up to date
+    config.migrate()?;
 
     // Then we do some work on it
     do_some_work(config);
@@ -40,13 +389,10 @@ fn normal_get_config(path: &str) -> anyhow::Result<()> {
 fn idiomatic_get_config(path: &str) -> anyhow::Result<()> {
 
 
-    /// Everything related to the `config` part is now abstracted into a single statement.
-    /// All the other variables are dropped once we have the final Config object.
-    let config = {
-        let raw_config = std::fs::read(path)?;
-        let config_str = String::from_utf8(raw_config)?;
-        toml::from_str(&config_str)?
-    };
+    /// Everything related to the `config` part is now abstracted into a single statement,
+    /// the block in `load_config`. All the other variables are dropped once we have the final
+    /// Config object.
+    let config = load_config(path)?;
 
     /// Now it is impossible to provide the incorrect value here, as there is only a single 
     /// variable in the function stack: the config: Config one. 
@@ -55,13 +401,117 @@ fn idiomatic_get_config(path: &str) -> anyhow::Result<()> {
     Ok(())
 }
 
+/// The block of `idiomatic_get_config`, returning the `Config` to whoever asked for it.
+fn load_config(path: &str) -> anyhow::Result<Config> {
+    // Each step says what it was doing, so a failure tells us which path and which stage broke.
+    let config = {
+        let raw_config = std::fs::read(path).with_context(|| format!("reading {path}"))?;
+        let config_str = String::from_utf8(raw_config).context("parsing UTF-8")?;
+        let mut config: Config = toml::from_str(&config_str).context("deserializing TOML")?;
+        config.migrate()?;
+        config
+    };
+
+    Ok(config)
+}
+
+/// Tries each path in order, like XDG config discovery, and returns the first config that loads.
+/// Only a path with no file is skipped: a file that is there but broken is an error, not a reason
+/// to quietly fall back to the next one. If no file exists, the error lists every path tried.
+#[allow(dead_code)]
+fn load_config_first_of(paths: &[&str]) -> anyhow::Result<Config> {
+    let mut attempts = Vec::new();
+    for path in paths {
+        match load_config(path) {
+            Ok(config) => return Ok(config),
+            Err(err) if is_not_found(&err) => attempts.push(format!("{path}: {err:#}")),
+            Err(err) => return Err(err),
+        }
+    }
+
+    anyhow::bail!("no config could be loaded, tried:\n{}", attempts.join("\n"))
+}
+
+fn is_not_found(err: &anyhow::Error) -> bool {
+    err.chain()
+        .filter_map(|cause| cause.downcast_ref::<std::io::Error>())
+        .any(|err| err.kind() == std::io::ErrorKind::NotFound)
+}
+
+/// Loads the config, trying again up to `retries` more times when the file can't be read (it may
+/// be being rewritten). Parse errors are not retried: the same bytes would fail the same way.
+#[allow(dead_code)]
+fn load_config_with_retries(path: &str, retries: usize, delay: Duration) -> anyhow::Result<Config> {
+    let mut attempt = 0;
+    loop {
+        match load_config(path) {
+            Err(err) if attempt < retries && err.chain().any(|cause| cause.is::<std::io::Error>()) => {
+                attempt += 1;
+                std::thread::sleep(delay);
+            },
+            result => return result,
+        }
+    }
+}
+
+/// Same as `idiomatic_get_config`, but a stray invalid byte (a mildly corrupted file) doesn't
+/// make the whole load fail. Invalid sequences become `U+FFFD` and TOML gets the rest.
+#[allow(dead_code)]
+fn load_config_lossy(path: &str) -> anyhow::Result<Config> {
+    let config = {
+        let raw_config = std::fs::read(path).with_context(|| format!("reading {path}"))?;
+        let config_str = String::from_utf8_lossy(&raw_config);
+        let mut config: Config = toml::from_str(&config_str).context("deserializing TOML")?;
+        config.migrate()?;
+        config
+    };
+
+    Ok(config)
+}
+
+impl Config {
+    /// Twelve-factor style: every field from an `APP_..` environment variable. Only
+    /// `APP_SHOULD_OPEN` may be missing, the config is built straight at the current version.
+    #[allow(dead_code)]
+    fn from_env() -> anyhow::Result<Config> {
+        Config::from_vars(|name| std::env::var(name).ok())
+    }
+
+    /// `from_env` with the lookup pulled out, so it can be fed anything that maps names to values.
+    fn from_vars(var: impl Fn(&str) -> Option<String>) -> anyhow::Result<Config> {
+        let required = |name: &str| var(name).with_context(|| format!("{name} is not set"));
+        fn parse<T: std::str::FromStr>(name: &str, value: String) -> anyhow::Result<T>
+        where
+            T::Err: std::error::Error + Send + Sync + 'static,
+        {
+            value.trim().parse().with_context(|| format!("parsing {name}={value:?}"))
+        }
+
+        let config = Config {
+            version: CURRENT_CONFIG_VERSION,
+            title: ConfigTitle::new(required("APP_TITLE")?).context("parsing APP_TITLE")?,
+            width: parse("APP_WIDTH", required("APP_WIDTH")?)?,
+            height: parse("APP_HEIGHT", required("APP_HEIGHT")?)?,
+            should_open: var("APP_SHOULD_OPEN").map(|v| parse("APP_SHOULD_OPEN", v)).transpose()?,
+            tail: parse("APP_TAIL", required("APP_TAIL")?)?,
+            window: WindowOptions::default(),
+            log_level: LogLevel::default(),
+        };
+
+        Ok(config)
+    }
+}
+
 
 fn do_some_work(config: Config) {
     println!("I'm doing some work on {:?}", config);
     println!("Work on -> {}", config.title);
-    println!("Total space: {}", config.width * config.height);
-    if config.should_open.is_some() {
-        println!("Does it open by default? {}", config.should_open.unwrap());
+    match config.area() {
+        Some(area) => println!("Total space: {}", area),
+        None => println!("Total space: too big to compute"),
+    }
+    if let Some(should_open) = config.should_open {
+        println!("Does it open by default? {}", should_open);
     }
 }
 
@@ -83,3 +533,394 @@ fn main() {
          }
     }
 }
+
+
+#[cfg(test)]
+mod tests {
+    use common::diff::count_changes;
+
+    use crate::*;
+
+    /// Writes `contents` to a file in the temporary directory and returns its path.
+    fn write_temp(name: &str, contents: &[u8]) -> String {
+        let path = std::env::temp_dir().join(format!("block-pattern-{}-{name}", std::process::id()));
+        std::fs::write(&path, contents).unwrap();
+        path.to_string_lossy().into_owned()
+    }
+
+    fn config_with(width: i32, height: i32) -> Config {
+        toml::from_str(&format!(
+            r#"
+            title = "hello"
+            width = {width}
+            height = {height}
+            tail = false
+            "#
+        )).unwrap()
+    }
+
+    #[test]
+    fn migrate_version_one() {
+        let mut config: Config = toml::from_str(
+            r#"
+            title = "hello"
+            width = 400
+            height = 300
+            tail = false
+            "#,
+        ).unwrap();
+        assert_eq!(config.version, 1);
+        assert_eq!(config.should_open, None);
+
+        config.migrate().unwrap();
+        assert_eq!(config.version, CURRENT_CONFIG_VERSION);
+        assert_eq!(config.should_open, Some(false));
+    }
+
+    #[test]
+    fn migrate_unknown_future_version() {
+        let mut config: Config = toml::from_str(
+            r#"
+            version = 99
+            title = "hello"
+            width = 400
+            height = 300
+            tail = false
+            "#,
+        ).unwrap();
+
+        let err = config.migrate().unwrap_err();
+        assert!(err.to_string().contains("99"));
+    }
+
+    #[test]
+    fn migrate_version_zero() {
+        let mut config: Config = toml::from_str(
+            r#"
+            version = 0
+            title = "hello"
+            width = 400
+            height = 300
+            tail = false
+            "#,
+        ).unwrap();
+
+        let err = config.migrate().unwrap_err();
+        assert!(err.to_string().contains("version 0"));
+        assert_eq!(config.version, 0);
+    }
+
+    #[test]
+    fn missing_file_error_mentions_path() {
+        let path = "does/not/exist.toml";
+        let err = idiomatic_get_config(path).unwrap_err();
+
+        assert!(err.to_string().contains(path));
+        // The io error that caused it is still in the chain.
+        assert!(err.chain().any(|cause| cause.downcast_ref::<std::io::Error>().is_some()));
+    }
+
+    #[test]
+    fn area() {
+        assert_eq!(config_with(400, 300).area(), Some(120_000));
+        // Would overflow as `i32 * i32`
+        assert_eq!(config_with(i32::MAX, 2).area(), Some(i32::MAX as i64 * 2));
+        assert_eq!(config_with(i32::MAX, i32::MAX).area(), Some(i32::MAX as i64 * i32::MAX as i64));
+        assert_eq!(config_with(i32::MIN, i32::MIN).area(), Some(i32::MIN as i64 * i32::MIN as i64));
+    }
+
+    #[test]
+    fn expand_vars_in_title() {
+        let vars = std::collections::HashMap::from([("APP_NAME", "block-pattern")]);
+        let lookup = |name: &str| vars.get(name).map(|v| v.to_string());
+
+        let mut cfg = config_with(400, 300);
+        cfg.title = ConfigTitle::new("${APP_NAME} config").unwrap();
+        cfg.expand_vars(lookup).unwrap();
+        assert_eq!(cfg.title.as_str(), "block-pattern config");
+
+        cfg.title = ConfigTitle::new("${UNDEFINED}").unwrap();
+        let err = cfg.expand_vars(lookup).unwrap_err();
+        assert!(err.to_string().contains("UNDEFINED"));
+    }
+
+    #[test]
+    fn expand_placeholders_edge_cases() {
+        let lookup = |name: &str| (name == "USER").then(|| String::from("hector"));
+
+        assert_eq!(expand_placeholders("hi ${USER}!", lookup).unwrap(), "hi hector!");
+        assert_eq!(expand_placeholders("${USER}${USER}", lookup).unwrap(), "hectorhector");
+        assert_eq!(expand_placeholders("costs $$5, or $5", lookup).unwrap(), "costs $5, or $5");
+        assert_eq!(expand_placeholders("$${USER}", lookup).unwrap(), "${USER}");
+        assert!(expand_placeholders("${USER", lookup).is_err());
+        assert!(expand_placeholders("${}", lookup).is_err());
+        assert!(expand_placeholders("${A${USER}}", lookup).is_err());
+    }
+
+    #[test]
+    fn diff_and_count_changes() {
+        let small = config_with(400, 300);
+        let wide = config_with(1920, 300);
+        assert_eq!(
+            small.diff(&wide),
+            vec![FieldChange { field: "width", from: String::from("400"), to: String::from("1920") }]
+        );
+
+        let mut big = config_with(1920, 1080);
+        big.should_open = Some(true);
+        assert_eq!(count_changes(&small, &big), 3);
+        assert_eq!(count_changes(&big, &big), 0);
+    }
+
+    #[test]
+    fn validate_title_in() {
+        let cfg = config_with(400, 300);
+        assert_eq!(cfg.validate_title_in(&["hello", "world"]), Ok(()));
+        assert_eq!(cfg.validate_title_in(&["HeLLo"]), Ok(()));
+        assert_eq!(cfg.validate_title_in(&["world"]), Err(ConfigError::TitleNotAllowed(String::from("hello"))));
+        assert_eq!(cfg.validate_title_in(&[]).unwrap_err().to_string(), "the title \"hello\" is not allowed");
+    }
+
+    #[test]
+    fn overlay_only_meaningful_fields() {
+        let mut base: Config = toml::from_str(
+            r#"
+            title = "base"
+            width = 1920
+            height = 1080
+            should_open = true
+            tail = true
+            [window.border]
+            width = 2
+            color = "red"
+            "#,
+        ).unwrap();
+        let sparse = config_with(0, 600);
+
+        base.overlay(&sparse);
+        assert_eq!(base.title.as_str(), "hello");
+        assert_eq!((base.width, base.height), (1920, 600));
+        assert_eq!(base.should_open, Some(true));
+        assert!(base.tail);
+        assert_eq!(base.window.border, Some(Border { width: 2, color: String::from("red") }));
+
+        let mut plain = config_with(400, 300);
+        plain.overlay(&config_with(400, 300).with_should_open(false));
+        assert_eq!(plain.should_open, Some(false));
+    }
+
+    #[test]
+    fn log_level() {
+        assert_eq!(config_with(400, 300).log_level, LogLevel::Info);
+
+        let levels = [
+            ("trace", LogLevel::Trace),
+            ("debug", LogLevel::Debug),
+            ("info", LogLevel::Info),
+            ("warn", LogLevel::Warn),
+            ("error", LogLevel::Error),
+        ];
+        for (name, level) in levels {
+            let cfg: Config = toml::from_str(&format!(
+                "title = \"hello\"\nwidth = 1\nheight = 1\ntail = false\nlog_level = \"{name}\"\n"
+            )).unwrap();
+            assert_eq!(cfg.log_level, level);
+            assert_eq!(level.to_string(), name);
+        }
+
+        let err = toml::from_str::<Config>("title = \"hello\"\nwidth = 1\nheight = 1\ntail = false\nlog_level = \"Loud\"\n")
+            .unwrap_err();
+        assert!(err.to_string().contains("unknown log level \"Loud\", expected one of trace, debug, info, warn, error"));
+    }
+
+    #[test]
+    fn clamp_dimensions() {
+        let mut cfg = config_with(100_000, -5);
+        cfg.clamp_dimensions(1, 4096);
+        assert_eq!((cfg.width, cfg.height), (4096, 1));
+
+        let mut cfg = config_with(400, 300);
+        cfg.clamp_dimensions(1, 4096);
+        assert_eq!((cfg.width, cfg.height), (400, 300));
+    }
+
+    #[test]
+    #[should_panic]
+    fn clamp_dimensions_inverted_range() {
+        config_with(400, 300).clamp_dimensions(10, 1);
+    }
+
+    #[test]
+    fn generated_setters() {
+        let cfg = config_with(400, 300);
+        assert_eq!(cfg.should_open, None);
+        let cfg = cfg.with_should_open(true);
+        assert_eq!(cfg.should_open, Some(true));
+
+        let window = WindowOptions::default().with_border(Border { width: 2, color: String::from("red") });
+        assert_eq!(window.border, Some(Border { width: 2, color: String::from("red") }));
+    }
+
+    #[test]
+    fn from_vars_reads_every_field() {
+        let vars = std::collections::HashMap::from([
+            ("APP_TITLE", "from env"),
+            ("APP_WIDTH", "800"),
+            ("APP_HEIGHT", " 600 "),
+            ("APP_SHOULD_OPEN", "true"),
+            ("APP_TAIL", "false"),
+        ]);
+        let cfg = Config::from_vars(|name| vars.get(name).map(|v| v.to_string())).unwrap();
+
+        assert_eq!(cfg.title.as_str(), "from env");
+        assert_eq!((cfg.width, cfg.height), (800, 600));
+        assert_eq!(cfg.should_open, Some(true));
+        assert!(!cfg.tail);
+        assert_eq!(cfg.version, CURRENT_CONFIG_VERSION);
+    }
+
+    #[test]
+    fn from_vars_errors() {
+        let mut vars = std::collections::HashMap::from([
+            ("APP_TITLE", "from env"),
+            ("APP_WIDTH", "800"),
+            ("APP_TAIL", "false"),
+        ]);
+        let err = Config::from_vars(|name| vars.get(name).map(|v| v.to_string())).unwrap_err();
+        assert_eq!(err.to_string(), "APP_HEIGHT is not set");
+
+        vars.insert("APP_HEIGHT", "tall");
+        let err = Config::from_vars(|name| vars.get(name).map(|v| v.to_string())).unwrap_err();
+        assert!(err.to_string().contains("APP_HEIGHT"));
+
+        let err = Config::from_vars(|_| None).unwrap_err();
+        assert_eq!(err.to_string(), "APP_TITLE is not set");
+    }
+
+    #[test]
+    fn aspect_ratio() {
+        let full_hd = config_with(1920, 1080);
+        assert!((full_hd.aspect_ratio().unwrap() - 16.0 / 9.0).abs() < 1e-9);
+        assert_eq!(full_hd.is_landscape(), Some(true));
+
+        let square = config_with(500, 500);
+        assert_eq!(square.aspect_ratio(), Some(1.0));
+        assert_eq!(square.is_landscape(), Some(false));
+        assert_eq!(config_with(1080, 1920).is_landscape(), Some(false));
+
+        let flat = config_with(400, 0);
+        assert_eq!(flat.aspect_ratio(), None);
+        assert_eq!(flat.is_landscape(), None);
+    }
+
+    #[test]
+    fn lossy_loader_tolerates_invalid_utf8() {
+        let path = write_temp(
+            "lossy.toml",
+            b"# corrupted \xff comment\ntitle = \"hello\"\nwidth = 400\nheight = 300\ntail = false\n",
+        );
+
+        // The strict loader refuses it...
+        let err = idiomatic_get_config(&path).unwrap_err();
+        assert!(err.chain().any(|cause| cause.downcast_ref::<std::string::FromUtf8Error>().is_some()));
+
+        // ...the lossy one gets the config anyway.
+        let config = load_config_lossy(&path).unwrap();
+        assert_eq!(config.title.as_str(), "hello");
+        assert_eq!(config.area(), Some(120_000));
+    }
+
+    #[test]
+    fn first_of_skips_missing_paths() {
+        let second = write_temp("second.toml", b"title = \"second\"\nwidth = 1\nheight = 1\ntail = true\n");
+        let config = load_config_first_of(&["does/not/exist.toml", &second]).unwrap();
+        assert_eq!(config.title.as_str(), "second");
+
+        let err = load_config_first_of(&["does/not/exist.toml", "neither/does/this.toml"]).unwrap_err();
+        assert!(err.to_string().contains("does/not/exist.toml"));
+        assert!(err.to_string().contains("neither/does/this.toml"));
+    }
+
+    #[test]
+    fn first_of_stops_at_broken_file() {
+        let broken = write_temp("broken.toml", b"title = \"broken\"\nwidth = \"wide\"\n");
+        let second = write_temp("second_after_broken.toml", b"title = \"second\"\nwidth = 1\nheight = 1\ntail = true\n");
+
+        let err = load_config_first_of(&["does/not/exist.toml", &broken, &second]).unwrap_err();
+        assert!(err.chain().any(|cause| cause.downcast_ref::<toml::de::Error>().is_some()));
+    }
+
+    #[test]
+    fn validate_with_surfaces_failure() {
+        let validators: [&dyn ConfigValidator; 2] = [&NonEmptyTitle, &PositiveDimensions];
+
+        assert_eq!(config_with(400, 300).validate_with(&validators), Ok(()));
+        assert_eq!(
+            config_with(400, 0).validate_with(&validators),
+            Err(String::from("dimensions must be positive, got 400x0"))
+        );
+    }
+
+    #[test]
+    fn window_border_sub_table() {
+        let without = write_temp("no-border.toml", b"title = \"hello\"\nwidth = 1\nheight = 1\ntail = true\n");
+        assert_eq!(load_config(&without).unwrap().window.border, None);
+
+        let with = write_temp(
+            "border.toml",
+            b"title = \"hello\"\nwidth = 1\nheight = 1\ntail = true\n\n[window.border]\nwidth = 2\ncolor = \"red\"\n",
+        );
+        assert_eq!(
+            load_config(&with).unwrap().window.border,
+            Some(Border { width: 2, color: String::from("red") })
+        );
+    }
+
+    #[test]
+    fn config_title() {
+        assert_eq!(ConfigTitle::new(""), Err(ConfigError::EmptyTitle));
+        assert_eq!(ConfigTitle::new("a".repeat(101)), Err(ConfigError::TitleTooLong(101)));
+        assert_eq!(ConfigTitle::new("hello").unwrap().as_str(), "hello");
+        assert!(ConfigTitle::new("a".repeat(100)).is_ok());
+
+        // The deserializer goes through the same checks
+        let err = toml::from_str::<Config>("title = \"\"\nwidth = 1\nheight = 1\ntail = true\n").unwrap_err();
+        assert!(err.to_string().contains("the title must not be empty"));
+    }
+
+    #[test]
+    fn retries_until_the_file_appears() {
+        let path = std::env::temp_dir()
+            .join(format!("block-pattern-{}-appears.toml", std::process::id()))
+            .to_string_lossy()
+            .into_owned();
+        let _ = std::fs::remove_file(&path);
+
+        let writer = {
+            let path = path.clone();
+            std::thread::spawn(move || {
+                std::thread::sleep(Duration::from_millis(50));
+                // `fs::write` creates the file before filling it, a retry in between would read it
+                // empty and get a parse error. A rename makes the whole file appear at once.
+                let partial = format!("{path}.partial");
+                std::fs::write(&partial, "title = \"late\"\nwidth = 1\nheight = 1\ntail = true\n").unwrap();
+                std::fs::rename(&partial, &path).unwrap();
+            })
+        };
+
+        let config = load_config_with_retries(&path, 50, Duration::from_millis(20)).unwrap();
+        assert_eq!(config.title.as_str(), "late");
+        writer.join().unwrap();
+
+        assert!(load_config_with_retries("does/not/exist.toml", 2, Duration::from_millis(1)).is_err());
+    }
+
+    #[test]
+    fn parse_errors_are_not_retried() {
+        let path = write_temp("invalid.toml", b"title = ");
+
+        let start = std::time::Instant::now();
+        assert!(load_config_with_retries(&path, 3, Duration::from_secs(10)).is_err());
+        assert!(start.elapsed() < Duration::from_secs(10));
+    }
+}
@@ -0,0 +1,67 @@
+use std::convert::Infallible;
+use std::fmt;
+
+use crate::person::Person;
+
+/// Everything that can go wrong while building a `Person`.
+#[allow(dead_code)]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BuilderError {
+    MissingField(&'static str),
+    UnknownField(String),
+    InvalidName(String),
+    InvalidEmail(String),
+    InvalidAge(u8),
+    InvalidPhone(String),
+}
+
+impl fmt::Display for BuilderError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BuilderError::MissingField(field) => write!(f, "missing required field: {}", field),
+            BuilderError::UnknownField(field) => write!(f, "unknown field: {}", field),
+            BuilderError::InvalidName(name) => write!(f, "invalid name {:?}: expected a name and a family name", name),
+            BuilderError::InvalidEmail(email) if !email.contains('@') => write!(f, "invalid email: missing '@'"),
+            BuilderError::InvalidEmail(email) => write!(f, "invalid email {:?}: expected user@domain.tld", email),
+            BuilderError::InvalidAge(age) => write!(f, "invalid age: {} is above {}", age, Person::MAX_AGE),
+            BuilderError::InvalidPhone(phone) => write!(f, "invalid phone: {:?}", phone),
+        }
+    }
+}
+
+impl std::error::Error for BuilderError {}
+
+// Lets setters take anything that converts into their type, fallibly (`u64` into `PhoneNumber`)
+// or not (a `PhoneNumber` itself), and `?` the conversion into a `BuilderError` either way.
+impl From<Infallible> for BuilderError {
+    fn from(never: Infallible) -> Self { match never {} }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::error::BuilderError;
+
+    #[test]
+    fn display() {
+        assert_eq!("missing required field: age", BuilderError::MissingField("age").to_string());
+        assert_eq!("unknown field: hobby", BuilderError::UnknownField(String::from("hobby")).to_string());
+        assert_eq!(
+            "invalid name \"Hector\": expected a name and a family name",
+            BuilderError::InvalidName(String::from("Hector")).to_string()
+        );
+        assert_eq!("invalid email: missing '@'", BuilderError::InvalidEmail(String::from("hector")).to_string());
+        assert_eq!(
+            "invalid email \"hector@localhost\": expected user@domain.tld",
+            BuilderError::InvalidEmail(String::from("hector@localhost")).to_string()
+        );
+        assert_eq!("invalid age: 200 is above 150", BuilderError::InvalidAge(200).to_string());
+        assert_eq!("invalid phone: \"12\"", BuilderError::InvalidPhone(String::from("12")).to_string());
+    }
+
+    #[test]
+    fn is_a_std_error() {
+        // Which means it also converts into `anyhow::Error` with `?`
+        let err: anyhow::Error = BuilderError::MissingField("name").into();
+        assert_eq!("missing required field: name", err.to_string());
+    }
+}
@@ -0,0 +1,53 @@
+use crate::person::Person;
+
+/// The people living under the same roof.
+#[allow(dead_code)]
+#[derive(Debug, Default)]
+pub struct Household {
+    members: Vec<Person>,
+}
+
+#[allow(dead_code)]
+impl Household {
+    pub fn from_members(members: Vec<Person>) -> Household {
+        Household { members }
+    }
+
+    /// The oldest member. With a tie, the one listed first.
+    pub fn head(&self) -> Option<&Person> {
+        self.members.iter().rev().max_by_key(|member| member.age())
+    }
+
+    pub fn size(&self) -> usize {
+        self.members.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::household::Household;
+    use crate::person::Person;
+
+    #[test]
+    fn oldest_member_is_head() {
+        let household = Household::from_members(vec![
+            Person::new(String::from("Hector"), String::from("Alarcon"), 28),
+            Person::new(String::from("Carmen"), String::from("Alarcon"), 61),
+            Person::new(String::from("Lucia"), String::from("Alarcon"), 5),
+        ]);
+
+        assert_eq!(3, household.size());
+        assert_eq!(Some("Carmen"), household.head().map(|head| head.name()));
+    }
+
+    #[test]
+    fn ties_and_empty() {
+        let household = Household::from_members(vec![
+            Person::new(String::from("Ana"), String::from("Ruiz"), 40),
+            Person::new(String::from("Juan"), String::from("Ruiz"), 40),
+        ]);
+        assert_eq!(Some("Ana"), household.head().map(|head| head.name()));
+
+        assert!(Household::from_members(Vec::new()).head().is_none());
+    }
+}
@@ -0,0 +1,32 @@
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+/// The language a person wants to be addressed in.
+#[allow(dead_code)]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+pub enum Language {
+    #[default]
+    English,
+    Spanish,
+}
+
+#[allow(dead_code)]
+impl Language {
+    pub fn hello(&self) -> &'static str {
+        match self {
+            Language::English => "Hello",
+            Language::Spanish => "Hola",
+        }
+    }
+
+    pub fn is_default(&self) -> bool {
+        *self == Language::default()
+    }
+}
+
+impl fmt::Display for Language {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self, f)
+    }
+}
@@ -1,12 +1,21 @@
+mod error;
+mod household;
+mod language;
+mod nationality;
+mod option_ext;
 mod person;
+mod person_builder;
+mod phone;
 
+use crate::error::BuilderError;
 use crate::person::Person;
 
-fn main() {
-    let person = Person::new(String::from("Hector"), String::from("Alarcon"), 28)
+fn main() -> Result<(), BuilderError> {
+    let person = Person::try_new(String::from("Hector"), String::from("Alarcon"), 28)?
         .with_job_title(String::from("Software Engineer"))
-        .with_phone(12345678)
+        .with_phone(12345678)?
         .with_nationality(String::from("Spanish"));
 
     println!("Person: {:?}", person);
+    Ok(())
 }
@@ -0,0 +1,27 @@
+use std::collections::HashSet;
+use std::rc::Rc;
+
+/// Thousands of people share a handful of nationalities. Instead of every `Person` owning its own
+/// `"Spanish"`, the pool keeps one `Rc<str>` per distinct value and hands out clones of it.
+#[allow(dead_code)]
+#[derive(Debug, Default)]
+pub struct NationalityPool {
+    values: HashSet<Rc<str>>,
+}
+
+#[allow(dead_code)]
+impl NationalityPool {
+    pub fn new() -> Self { Self::default() }
+
+    pub fn intern(&mut self, s: &str) -> Rc<str> {
+        if let Some(existing) = self.values.get(s) {
+            return Rc::clone(existing);
+        }
+        let value: Rc<str> = Rc::from(s);
+        self.values.insert(Rc::clone(&value));
+        value
+    }
+
+    pub fn len(&self) -> usize { self.values.len() }
+    pub fn is_empty(&self) -> bool { self.values.is_empty() }
+}
@@ -0,0 +1,45 @@
+/// `Option::get_or_insert_with`, but for a closure that can fail: a lookup in a file, a parse...
+/// On error the `Option` is left untouched and the error is handed back to the caller.
+///
+/// The standard library has an unstable method with the same name. Like the two factorials in
+/// the extension traits example, call it with its fully qualified name to avoid the collision:
+/// `OrInsertWithResult::get_or_try_insert_with(&mut option, || ...)`.
+#[allow(dead_code)]
+pub trait OrInsertWithResult<T> {
+    fn get_or_try_insert_with<E>(&mut self, f: impl FnOnce() -> Result<T, E>) -> Result<&mut T, E>;
+}
+
+impl<T> OrInsertWithResult<T> for Option<T> {
+    fn get_or_try_insert_with<E>(&mut self, f: impl FnOnce() -> Result<T, E>) -> Result<&mut T, E> {
+        if self.is_none() {
+            *self = Some(f()?);
+        }
+        // Just filled it if it was empty
+        Ok(self.as_mut().unwrap())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::option_ext::OrInsertWithResult;
+
+    #[test]
+    fn inserts_on_success() {
+        let mut job_title: Option<String> = None;
+        let value = OrInsertWithResult::get_or_try_insert_with(&mut job_title, || Ok::<_, String>(String::from("Software Engineer")));
+        assert_eq!(Ok(&mut String::from("Software Engineer")), value);
+        assert_eq!(Some(String::from("Software Engineer")), job_title);
+
+        // Already there: the closure is not even called
+        let value = OrInsertWithResult::get_or_try_insert_with(&mut job_title, || Err(String::from("not called")));
+        assert_eq!(Ok(&mut String::from("Software Engineer")), value);
+    }
+
+    #[test]
+    fn leaves_none_on_error() {
+        let mut age: Option<u8> = None;
+        let value = OrInsertWithResult::get_or_try_insert_with(&mut age, || "not a number".parse::<u8>());
+        assert!(value.is_err());
+        assert_eq!(None, age);
+    }
+}
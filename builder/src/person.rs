@@ -1,20 +1,56 @@
+use std::fmt;
+use std::io::{self, Write};
+use std::rc::Rc;
+use std::str::FromStr;
 
-#[allow(dead_code)]
-#[derive(Debug, Default)]
-pub struct Person {
-    name: String,
-    family_name: String,
-    age: u8,
-    phone: Option<u64>,
-    home_address: Option<String>,
-    job_title: Option<String>,
-    education: Option<String>,
-    residency: Option<String>,
-    nationality: Option<String>,
+use common::diff::{describe, Changes, Diffable, FieldChange};
+use anyhow::Context;
+use common::optional_builder;
+use extensiontraits::urgency::Urgency;
+use serde::de::Error as _;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::error::BuilderError;
+use crate::language::Language;
+use crate::phone::PhoneNumber;
+
+// `with_home_address`, `with_job_title`... The fields that accept anything get their setters
+// from the macro. `phone` and `email` are validated, their setters are below.
+optional_builder! {
+    #[allow(dead_code)]
+    #[derive(Debug, Default, PartialEq, Deserialize, Serialize)]
+    pub struct Person {
+        name: String,
+        family_name: String,
+        age: u8,
+        #[no_setter]
+        #[serde(skip_serializing_if = "Option::is_none")]
+        phone: Option<PhoneNumber>,
+        #[no_setter]
+        #[serde(skip_serializing_if = "Option::is_none")]
+        email: Option<String>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        home_address: Option<String>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        job_title: Option<String>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        education: Option<String>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        residency: Option<String>,
+        /// Shared with every other person of the same nationality, see `NationalityPool`.
+        #[serde(skip_serializing_if = "Option::is_none")]
+        nationality: Option<Rc<str>>,
+        /// English unless said otherwise, and only written out when it isn't.
+        #[serde(default, skip_serializing_if = "Language::is_default")]
+        language: Language,
+    }
 }
 
 #[allow(dead_code)]
 impl Person {
+    /// Doesn't check `age`, for values that are known to be fine (tests, constants). Anything
+    /// that comes from outside goes through `try_new`.
     pub fn new(
         name: String, 
         family_name: String, 
@@ -28,10 +64,846 @@ impl Person {
         }
     }
 
-    pub fn with_phone(self, phone:u64) -> Self { Self { phone: Some(phone), ..self } }
-    pub fn with_home_address(self, home_address : String) -> Self { Self {home_address: Some(home_address), ..self}}
-    pub fn with_job_title(self, job_title: String) -> Self { Self {job_title : Some(job_title), ..self} }
-    pub fn with_education(self, education: String) -> Self { Self {education : Some(education), ..self} }
-    pub fn with_residency(self, residency: String) -> Self { Self { residency : Some(residency), ..self } }
-    pub fn with_nationality(self, nationality: String) -> Self { Self {nationality : Some(nationality), ..self}  }
+    /// `new`, but the age is validated like `with_age` does.
+    pub fn try_new(name: String, family_name: String, age: u8) -> Result<Self, BuilderError> {
+        validate_age(age)?;
+        Ok(Person::new(name, family_name, age))
+    }
+
+    pub fn name(&self) -> &str { &self.name }
+    pub fn family_name(&self) -> &str { &self.family_name }
+    pub fn age(&self) -> u8 { self.age }
+}
+
+#[allow(dead_code)]
+impl Person {
+    pub fn with_language(self, language: Language) -> Self { Self { language, ..self } }
+
+    /// "Hello Hector!" or "¡Hola Hector!", depending on `language`. The marks come from the
+    /// `Urgency` extension trait of the extensiontraits crate.
+    pub fn greeting(&self) -> String {
+        let mut greeting = format!("{} {}", self.language.hello(), self.name);
+        match self.language {
+            Language::English => greeting.add_urgency(),
+            Language::Spanish => greeting.add_urgency_in_spanish(),
+        }
+        greeting
+    }
+}
+
+/// The three required fields are enough to build a `Person`, through `try_new`:
+/// `let person: Person = (name, family_name, age).try_into()?;`
+impl TryFrom<(String, String, u8)> for Person {
+    type Error = BuilderError;
+
+    fn try_from((name, family_name, age): (String, String, u8)) -> Result<Self, Self::Error> {
+        Person::try_new(name, family_name, age)
+    }
+}
+
+#[allow(dead_code)]
+impl Person {
+    /// Splits `"Maria Jose Garcia"` on the last whitespace: `"Maria Jose"` is the name and
+    /// `"Garcia"` the family name. A single word has no family name, so it is an error.
+    pub fn from_full_name(full: &str, age: u8) -> Result<Person, BuilderError> {
+        let (name, family_name) = full
+            .trim()
+            .rsplit_once(char::is_whitespace)
+            .ok_or_else(|| BuilderError::InvalidName(full.to_string()))?;
+
+        Person::try_new(name.trim_end().to_string(), family_name.to_string(), age)
+    }
+}
+
+/// Some fields can hold invalid values: an email without `@`, an impossible age or a phone with
+/// too few digits. Those setters validate their input and return `Result<Self, BuilderError>`
+/// instead of `Self`, so a chain stays fluent with `?` and stops at the first invalid value:
+///
+/// ```Rust
+/// let person = Person::try_new(String::from("Hector"), String::from("Alarcon"), 28)?
+///     .with_email("hector@example.com")?
+///     .with_phone(PhoneNumber::from_str("+34 600 000 000")?)?;
+/// ```
+///
+/// There is no final `build()`: a `Person` that started at `try_new` and made it through the
+/// chain is already valid.
+#[allow(dead_code)]
+impl Person {
+    pub(crate) const MAX_AGE: u8 = 150;
+
+    pub fn with_age(self, age: u8) -> Result<Self, BuilderError> {
+        validate_age(age)?;
+        Ok(Self { age, ..self })
+    }
+
+    pub fn with_email(self, email: impl Into<String>) -> Result<Self, BuilderError> {
+        let email = email.into();
+        validate_email(&email)?;
+        Ok(Self { email: Some(email), ..self })
+    }
+
+    pub fn with_phone<P>(self, phone: P) -> Result<Self, BuilderError>
+    where
+        P: TryInto<PhoneNumber>,
+        BuilderError: From<P::Error>,
+    {
+        Ok(Self { phone: Some(phone.try_into()?), ..self })
+    }
+}
+
+/// Conditional setters, so an `if` doesn't have to break the chain apart:
+///
+/// ```Rust
+/// let person = Person::new(String::from("Hector"), String::from("Alarcon"), 28)
+///     .with_if(is_employed, |p| p.with_job_title(String::from("Software Engineer")))
+///     .try_with_if(has_phone, |p| p.with_phone(612345678))?;
+/// ```
+#[allow(dead_code)]
+impl Person {
+    pub fn with_if(self, cond: bool, f: impl FnOnce(Self) -> Self) -> Self {
+        if cond { f(self) } else { self }
+    }
+
+    /// The same for the fallible setters: the closure's error is only possible when `cond` holds.
+    pub fn try_with_if(self, cond: bool, f: impl FnOnce(Self) -> Result<Self, BuilderError>) -> Result<Self, BuilderError> {
+        if cond { f(self) } else { Ok(self) }
+    }
+}
+
+fn validate_age(age: u8) -> Result<(), BuilderError> {
+    if age > Person::MAX_AGE {
+        return Err(BuilderError::InvalidAge(age));
+    }
+    Ok(())
+}
+
+fn validate_email(email: &str) -> Result<(), BuilderError> {
+    match email.split_once('@') {
+        Some((user, domain)) if !user.is_empty() && domain.contains('.') && !domain.contains('@') => Ok(()),
+        _ => Err(BuilderError::InvalidEmail(email.to_string())),
+    }
+}
+
+#[allow(dead_code)]
+impl Person {
+    /// The setters stop at the first invalid value. A form wants to show every problem at once,
+    /// so this runs all the checks and collects every error. The phone isn't among them, a
+    /// `PhoneNumber` can't be invalid.
+    pub fn validate_all(&self) -> Result<(), Vec<BuilderError>> {
+        let errors: Vec<BuilderError> = [
+            validate_age(self.age),
+            self.email.as_deref().map_or(Ok(()), validate_email),
+        ]
+        .into_iter()
+        .filter_map(Result::err)
+        .collect();
+
+        if errors.is_empty() { Ok(()) } else { Err(errors) }
+    }
+}
+
+/// The errors of `validate_all` as one message, for the loaders that can only report one.
+fn join_errors(errors: &[BuilderError]) -> String {
+    errors.iter().map(ToString::to_string).collect::<Vec<_>>().join("; ")
+}
+
+#[allow(dead_code)]
+impl Person {
+    /// Applies `(field, value)` pairs through the matching `with_..` setter, for when the fields
+    /// are only known at runtime (a form, a CSV header...). The required fields are set by `new`,
+    /// so only the optional ones are recognized.
+    pub fn with_fields(self, pairs: impl IntoIterator<Item = (String, String)>) -> Result<Self, BuilderError> {
+        pairs.into_iter().try_fold(self, |person, (field, value)| {
+            Ok(match field.as_str() {
+                "phone" => person.with_phone(PhoneNumber::from_str(&value)?)?,
+                "email" => person.with_email(value)?,
+                "home_address" => person.with_home_address(value),
+                "job_title" => person.with_job_title(value),
+                "education" => person.with_education(value),
+                "residency" => person.with_residency(value),
+                "nationality" => person.with_nationality(value),
+                _ => return Err(BuilderError::UnknownField(field)),
+            })
+        })
+    }
+}
+
+#[allow(dead_code)]
+impl Person {
+    /// Loads a base person from a TOML file and lets the caller tweak it with the usual `with_..`
+    /// setters: a defaults file plus code overrides. The file is validated first, overrides can't
+    /// hide a bad value in it.
+    pub fn from_toml_with_overrides(path: &str, f: impl FnOnce(Person) -> Person) -> anyhow::Result<Person> {
+        let base: Person = {
+            let raw_person = std::fs::read_to_string(path).with_context(|| format!("reading {path}"))?;
+            toml::from_str(&raw_person).with_context(|| format!("deserializing TOML from {path}"))?
+        };
+        // The file skipped the setters, check it before the overrides build on top of it
+        base.validate_all().map_err(|errors| anyhow::anyhow!("invalid person in {path}: {}", join_errors(&errors)))?;
+
+        Ok(f(base))
+    }
+}
+
+#[allow(dead_code)]
+impl Person {
+    /// Unset optional fields are left out of the JSON instead of written as `null`.
+    pub fn to_json(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string(self)
+    }
+
+    /// Deserializing skips the setters, so the result goes through `validate_all` before it's
+    /// handed out. Every failed check ends up in the one error, separated by `; `.
+    pub fn from_json(s: &str) -> Result<Person, serde_json::Error> {
+        let person: Person = serde_json::from_str(s)?;
+        person.validate_all().map_err(|errors| serde_json::Error::custom(join_errors(&errors)))?;
+        Ok(person)
+    }
+}
+
+/// Imports one JSON `Person` per line. A bad line doesn't stop the import: it ends up in the
+/// second vector with its (zero-based) line number and the parsing or validation error. Blank
+/// lines are skipped.
+#[allow(dead_code)]
+pub fn from_ndjson(input: &str) -> (Vec<Person>, Vec<(usize, String)>) {
+    let mut people = Vec::new();
+    let mut errors = Vec::new();
+
+    for (index, line) in input.lines().enumerate().filter(|(_, line)| !line.trim().is_empty()) {
+        match Person::from_json(line) {
+            Ok(person) => people.push(person),
+            Err(err) => errors.push((index, err.to_string())),
+        }
+    }
+
+    (people, errors)
+}
+
+/// The other way around: one JSON `Person` per line, each line ending in `\n`. Every person is
+/// written as soon as it's serialized, wrap `writer` in a `BufWriter` if that's too many writes.
+#[allow(dead_code)]
+pub fn write_ndjson<W: Write>(people: &[Person], mut writer: W) -> io::Result<()> {
+    for person in people {
+        serde_json::to_writer(&mut writer, person)?;
+        writer.write_all(b"\n")?;
+    }
+    writer.flush()
+}
+
+#[allow(dead_code)]
+impl Person {
+    /// Scrubs everything that identifies or contacts the person, keeping the rest (age,
+    /// nationality, job...) for statistics.
+    pub fn anonymize(self) -> Person {
+        Self {
+            name: String::from("REDACTED"),
+            family_name: String::from("REDACTED"),
+            phone: None,
+            email: None,
+            home_address: None,
+            ..self
+        }
+    }
+}
+
+impl Diffable for Person {
+    fn diff(&self, other: &Person) -> Vec<FieldChange> {
+        let mut changes = Changes::default();
+
+        changes.compare("name", self.name.clone(), other.name.clone());
+        changes.compare("family_name", self.family_name.clone(), other.family_name.clone());
+        changes.compare("age", self.age.to_string(), other.age.to_string());
+        changes.compare("phone", describe(&self.phone), describe(&other.phone));
+        changes.compare("email", describe(&self.email), describe(&other.email));
+        changes.compare("home_address", describe(&self.home_address), describe(&other.home_address));
+        changes.compare("job_title", describe(&self.job_title), describe(&other.job_title));
+        changes.compare("education", describe(&self.education), describe(&other.education));
+        changes.compare("residency", describe(&self.residency), describe(&other.residency));
+        changes.compare("nationality", describe(&self.nationality), describe(&other.nationality));
+        changes.compare("language", self.language.to_string(), other.language.to_string());
+
+        changes.into_vec()
+    }
+}
+
+#[allow(dead_code)]
+impl Person {
+    /// For golden files: parses `expected_json` and compares it with `self` through `diff`.
+    /// The error lists every mismatching field, one per line, instead of two whole structs.
+    pub fn assert_matches_json(&self, expected_json: &str) -> Result<(), String> {
+        let expected = Person::from_json(expected_json).map_err(|err| format!("invalid expected JSON: {err}"))?;
+
+        let changes = expected.diff(self);
+        if changes.is_empty() {
+            return Ok(());
+        }
+        Err(changes
+            .iter()
+            .map(|change| format!("{}: expected {:?}, got {:?}", change.field, change.from, change.to))
+            .collect::<Vec<_>>()
+            .join("\n"))
+    }
+}
+
+#[allow(dead_code)]
+impl Person {
+    /// A stable ID to link records of the same person without storing who they are: the
+    /// SHA-256 of the salt, name, family name and age, in hex. Same person and salt, same ID.
+    /// Every part is followed by a zero byte, so `("ab", "c")` and `("a", "bc")` don't collide.
+    pub fn pseudonym_id(&self, salt: &str) -> String {
+        let mut hasher = Sha256::new();
+        for part in [salt, &self.name, &self.family_name, &self.age.to_string()] {
+            hasher.update(part.as_bytes());
+            hasher.update([0]);
+        }
+        hasher.finalize().iter().map(|byte| format!("{byte:02x}")).collect()
+    }
+}
+
+#[allow(dead_code)]
+impl Person {
+    /// The phone in readable groups, see `PhoneNumber::grouped`.
+    pub fn formatted_phone(&self) -> Option<String> {
+        self.phone.map(|phone| phone.grouped())
+    }
+}
+
+#[allow(dead_code)]
+impl Person {
+    /// A minimal vCard 3.0 for contact export. Unset contact fields get no line at all.
+    /// The format asks for CRLF line endings.
+    pub fn to_vcard(&self) -> String {
+        let mut lines = vec![
+            String::from("BEGIN:VCARD"),
+            String::from("VERSION:3.0"),
+            format!("N:{};{};;;", escape_vcard(&self.family_name), escape_vcard(&self.name)),
+            format!("FN:{} {}", escape_vcard(&self.name), escape_vcard(&self.family_name)),
+        ];
+        if let Some(phone) = self.phone {
+            lines.push(format!("TEL:{}", phone));
+        }
+        if let Some(email) = &self.email {
+            lines.push(format!("EMAIL:{}", escape_vcard(email)));
+        }
+        lines.push(String::from("END:VCARD"));
+
+        lines.join("\r\n") + "\r\n"
+    }
+}
+
+/// Escapes a vCard text value (RFC 2426, 4): `\`, `;` and `,` get a backslash, so a `;` in a
+/// name doesn't start a new `N` component, and a newline becomes a literal `\n`.
+fn escape_vcard(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    let mut chars = value.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' | ';' | ',' => {
+                escaped.push('\\');
+                escaped.push(c);
+            },
+            // `\r\n`, a lone `\r` and `\n` are all one line break
+            '\r' => {
+                chars.next_if_eq(&'\n');
+                escaped.push_str("\\n");
+            },
+            '\n' => escaped.push_str("\\n"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// A view of a `Person` that is safe to log: it keeps the name and job title, masks the phone
+/// and leaves the home address out.
+#[allow(dead_code)]
+pub struct Redacted<'a>(pub &'a Person);
+
+#[allow(dead_code)]
+impl Person {
+    pub fn redacted(&self) -> Redacted<'_> { Redacted(self) }
+}
+
+impl fmt::Display for Redacted<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let person = self.0;
+        write!(f, "{} {}", person.name, person.family_name)?;
+        if let Some(job_title) = &person.job_title {
+            write!(f, " ({})", job_title)?;
+        }
+        if person.phone.is_some() {
+            write!(f, ", phone: ***")?;
+        }
+        Ok(())
+    }
+}
+
+/// Sample people for tests. Everything is fixed, so assertions can rely on the exact values.
+#[cfg(test)]
+impl Person {
+    pub fn fixture() -> Person {
+        Person {
+            name: String::from("Hector"),
+            family_name: String::from("Alarcon"),
+            age: 28,
+            phone: Some(PhoneNumber::try_from(612345678).unwrap()),
+            email: Some(String::from("hector@example.com")),
+            home_address: Some(String::from("Calle Mayor 1, Madrid")),
+            job_title: Some(String::from("Software Engineer")),
+            education: Some(String::from("Computer Science")),
+            residency: Some(String::from("Madrid")),
+            nationality: Some(Rc::from("Spanish")),
+            language: Language::Spanish,
+        }
+    }
+
+    /// `n` fixtures that only differ in the name suffix (`Hector1`, `Hector2`...) and the age.
+    pub fn fixtures(n: usize) -> Vec<Person> {
+        (1..=n)
+            .map(|i| Person {
+                name: format!("Hector{i}"),
+                age: 18 + (i % 100) as u8,
+                ..Person::fixture()
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::rc::Rc;
+    use std::str::FromStr;
+
+    use crate::error::BuilderError;
+    use crate::nationality::NationalityPool;
+    use common::diff::{count_changes, Diffable, FieldChange};
+    use crate::language::Language;
+    use crate::person::{self, Person};
+    use crate::phone::PhoneNumber;
+
+    #[test]
+    fn fallible_chain() -> Result<(), BuilderError> {
+        let person = Person::new(String::from("Hector"), String::from("Alarcon"), 28)
+            .with_email("x@y.z")?
+            .with_phone(PhoneNumber::from_str("+34 600")?)?;
+
+        assert_eq!(Some(String::from("x@y.z")), person.email);
+        assert_eq!(Some(PhoneNumber::try_from(34600)?), person.phone);
+        Ok(())
+    }
+
+    #[test]
+    fn fallible_chain_stops_at_invalid_value() {
+        let person = Person::new(String::from("Hector"), String::from("Alarcon"), 28)
+            .with_email("not-an-email")
+            .and_then(|p| p.with_age(30));
+        assert_eq!(BuilderError::InvalidEmail(String::from("not-an-email")), person.unwrap_err());
+
+        let age = Person::new(String::from("Hector"), String::from("Alarcon"), 28).with_age(200);
+        assert_eq!(BuilderError::InvalidAge(200), age.unwrap_err());
+
+        assert_eq!(BuilderError::InvalidPhone(String::from("12")), PhoneNumber::from_str("12").unwrap_err());
+    }
+
+    #[test]
+    fn conditional_setters() -> Result<(), BuilderError> {
+        let base = || Person::new(String::from("Hector"), String::from("Alarcon"), 28);
+
+        let person = base().try_with_if(true, |p| p.with_phone(612345678))?;
+        assert_eq!(Some(PhoneNumber::try_from(612345678)?), person.phone);
+        let person = base().try_with_if(false, |p| p.with_phone(612345678))?;
+        assert_eq!(None, person.phone);
+        // An invalid value only fails when it's actually set
+        assert!(base().try_with_if(true, |p| p.with_phone(123)).is_err());
+        assert!(base().try_with_if(false, |p| p.with_phone(123)).is_ok());
+
+        let person = base()
+            .with_if(true, |p| p.with_job_title(String::from("Software Engineer")))
+            .with_if(false, |p| p.with_residency(String::from("Madrid")));
+        assert_eq!(Some(String::from("Software Engineer")), person.job_title);
+        assert_eq!(None, person.residency);
+        Ok(())
+    }
+
+    #[test]
+    fn generated_setters() {
+        let person = Person::new(String::from("Hector"), String::from("Alarcon"), 28)
+            .with_home_address("Calle Mayor 1")
+            .with_job_title(String::from("Software Engineer"))
+            .with_nationality("Spanish");
+
+        assert_eq!(Some(String::from("Calle Mayor 1")), person.home_address);
+        assert_eq!(Some(String::from("Software Engineer")), person.job_title);
+        assert_eq!(Some(Rc::from("Spanish")), person.nationality);
+        assert_eq!(None, person.education);
+
+        let person = person.with_job_title("Staff Engineer");
+        assert_eq!(Some(String::from("Staff Engineer")), person.job_title);
+    }
+
+    #[test]
+    fn greeting_follows_language() {
+        let person = Person::new(String::from("Hector"), String::from("Alarcon"), 28);
+        assert_eq!("Hello Hector!", person.greeting());
+
+        let person = person.with_language(Language::Spanish);
+        assert_eq!("¡Hola Hector!", person.greeting());
+
+        // Only a non-default language makes it to the JSON
+        assert!(person.to_json().unwrap().contains(r#""language":"Spanish""#));
+        assert_eq!(person, Person::from_json(&person.to_json().unwrap()).unwrap());
+    }
+
+    #[test]
+    fn pseudonym_id() -> Result<(), BuilderError> {
+        let person = Person::fixture();
+        let id = person.pseudonym_id("pepper");
+        assert_eq!(64, id.len());
+        assert!(id.chars().all(|c| c.is_ascii_hexdigit()));
+
+        // Deterministic, and contact details don't matter
+        assert_eq!(id, Person::fixture().with_phone(34600000000)?.pseudonym_id("pepper"));
+        assert_ne!(id, person.pseudonym_id("salt"));
+        assert_ne!(id, Person::fixture().with_age(29)?.pseudonym_id("pepper"));
+        Ok(())
+    }
+
+    #[test]
+    fn redacted_hides_phone() -> Result<(), BuilderError> {
+        let person = Person::new(String::from("Hector"), String::from("Alarcon"), 28)
+            .with_job_title(String::from("Software Engineer"))
+            .with_home_address(String::from("Calle Falsa 123"))
+            .with_phone(612345678)?;
+
+        let redacted = person.redacted().to_string();
+        assert!(redacted.contains("Hector"));
+        assert!(redacted.contains("Software Engineer"));
+        assert!(redacted.contains("***"));
+        assert!(!redacted.contains("612345678"));
+        assert!(!redacted.contains("Calle Falsa"));
+        Ok(())
+    }
+
+    #[test]
+    fn anonymize_scrubs_identifying_fields() -> Result<(), BuilderError> {
+        let person = Person::new(String::from("Hector"), String::from("Alarcon"), 28)
+            .with_nationality(String::from("Spanish"))
+            .with_home_address(String::from("Calle Falsa 123"))
+            .with_email("hector@example.com")?
+            .with_phone(612345678)?
+            .anonymize();
+
+        assert_eq!("REDACTED", person.name);
+        assert_eq!("REDACTED", person.family_name);
+        assert_eq!(None, person.phone);
+        assert_eq!(None, person.email);
+        assert_eq!(None, person.home_address);
+        assert_eq!(28, person.age);
+        assert_eq!(Some("Spanish"), person.nationality.as_deref());
+        Ok(())
+    }
+
+    #[test]
+    fn from_toml_with_overrides() -> anyhow::Result<()> {
+        let path = concat!(env!("CARGO_MANIFEST_DIR"), "/person.toml");
+        let person = Person::from_toml_with_overrides(path, |p| p.with_job_title(String::from("Staff Engineer")))?;
+
+        assert_eq!("Hector", person.name);
+        assert_eq!(28, person.age);
+        assert_eq!(Some("Spanish"), person.nationality.as_deref());
+        assert_eq!(Some(String::from("Staff Engineer")), person.job_title);
+        Ok(())
+    }
+
+    #[test]
+    fn from_toml_with_overrides_validates_the_file() {
+        let path = std::env::temp_dir().join("builder_invalid_person.toml");
+        std::fs::write(&path, "name = \"Hector\"\nfamily_name = \"Alarcon\"\nage = 200\n").unwrap();
+
+        // Even an override that would fix the age doesn't get to run
+        let err = Person::from_toml_with_overrides(path.to_str().unwrap(), |p| Person { age: 28, ..p }).unwrap_err();
+        assert!(err.to_string().contains("invalid age: 200 is above 150"));
+    }
+
+    #[test]
+    fn from_toml_with_overrides_names_the_file() {
+        let err = Person::from_toml_with_overrides("does/not/exist.toml", |p| p).unwrap_err();
+        assert_eq!("reading does/not/exist.toml", err.to_string());
+        assert!(err.chain().any(|cause| cause.downcast_ref::<std::io::Error>().is_some()));
+
+        let path = std::env::temp_dir().join("builder_broken_person.toml");
+        std::fs::write(&path, "name = ").unwrap();
+        let path = path.to_str().unwrap();
+        let err = Person::from_toml_with_overrides(path, |p| p).unwrap_err();
+        assert_eq!(format!("deserializing TOML from {path}"), err.to_string());
+    }
+
+    #[test]
+    fn diff_reports_changed_fields() {
+        let before = Person::new(String::from("Hector"), String::from("Alarcon"), 28)
+            .with_job_title(String::from("Software Engineer"));
+        let after = Person::new(String::from("Hector"), String::from("Alarcon"), 28)
+            .with_job_title(String::from("Staff Engineer"));
+
+        assert_eq!(
+            vec![FieldChange {
+                field: "job_title",
+                from: String::from("Software Engineer"),
+                to: String::from("Staff Engineer"),
+            }],
+            before.diff(&after)
+        );
+        assert!(before.diff(&before).is_empty());
+
+        let older = Person::new(String::from("Hector"), String::from("Alarcon"), 29);
+        let changes = before.diff(&older);
+        assert_eq!(vec!["age", "job_title"], changes.iter().map(|c| c.field).collect::<Vec<_>>());
+        assert_eq!("", changes[1].to);
+    }
+
+    #[test]
+    fn count_changes_through_the_trait() {
+        let before = Person::new(String::from("Hector"), String::from("Alarcon"), 28);
+        let after = Person::new(String::from("Hector"), String::from("Garcia"), 29)
+            .with_residency(String::from("Madrid"));
+
+        assert_eq!(3, count_changes(&before, &after));
+        assert_eq!(0, count_changes(&after, &after));
+    }
+
+    #[test]
+    fn assert_matches_json() -> Result<(), BuilderError> {
+        let person = Person::new(String::from("Hector"), String::from("Alarcon"), 28)
+            .with_job_title(String::from("Software Engineer"));
+        assert_eq!(Ok(()), person.assert_matches_json(
+            r#"{"name":"Hector","family_name":"Alarcon","age":28,"job_title":"Software Engineer"}"#
+        ));
+
+        let person = person.with_age(29)?.with_email("hector@example.com")?;
+        let err = person
+            .assert_matches_json(r#"{"name":"Hector","family_name":"Alarcon","age":28,"job_title":"Software Engineer"}"#)
+            .unwrap_err();
+        assert_eq!("age: expected \"28\", got \"29\"\nemail: expected \"\", got \"hector@example.com\"", err);
+
+        assert!(person.assert_matches_json("not json").unwrap_err().starts_with("invalid expected JSON"));
+        Ok(())
+    }
+
+    #[test]
+    fn validate_all_collects_every_error() {
+        // Built by hand to skip the validating setters.
+        let person = Person {
+            age: 200,
+            email: Some(String::from("not-an-email")),
+            ..Person::new(String::from("Hector"), String::from("Alarcon"), 28)
+        };
+
+        assert_eq!(
+            Err(vec![
+                BuilderError::InvalidAge(200),
+                BuilderError::InvalidEmail(String::from("not-an-email")),
+            ]),
+            person.validate_all()
+        );
+
+        let valid = Person::new(String::from("Hector"), String::from("Alarcon"), 28);
+        assert_eq!(Ok(()), valid.validate_all());
+    }
+
+    #[test]
+    fn from_required_fields_tuple() -> Result<(), BuilderError> {
+        let person: Person = (String::from("Hector"), String::from("Alarcon"), 28).try_into()?;
+
+        assert_eq!("Hector", person.name);
+        assert_eq!("Alarcon", person.family_name);
+        assert_eq!(28, person.age);
+        assert_eq!(None, person.job_title);
+
+        let too_old: Result<Person, _> = (String::from("Hector"), String::from("Alarcon"), 200).try_into();
+        assert_eq!(Err(BuilderError::InvalidAge(200)), too_old);
+        Ok(())
+    }
+
+    #[test]
+    fn try_new_validates_age() {
+        assert!(Person::try_new(String::from("Hector"), String::from("Alarcon"), 28).is_ok());
+        assert_eq!(
+            BuilderError::InvalidAge(200),
+            Person::try_new(String::from("Hector"), String::from("Alarcon"), 200).unwrap_err()
+        );
+        assert_eq!(BuilderError::InvalidAge(200), Person::from_full_name("Hector Alarcon", 200).unwrap_err());
+    }
+
+    #[test]
+    fn formatted_phone() -> Result<(), BuilderError> {
+        let person = Person::new(String::from("Hector"), String::from("Alarcon"), 28);
+        assert_eq!(None, person.formatted_phone());
+
+        let person = person.with_phone(612345678)?;
+        assert_eq!(Some(String::from("612 345 678")), person.formatted_phone());
+
+        let person = person.with_phone(34612345678)?;
+        assert_eq!(Some(String::from("34 612 345 678")), person.formatted_phone());
+        assert_eq!(Some(String::from("12 345")), person.with_phone(12345)?.formatted_phone());
+
+        // Reads back into the same number
+        assert_eq!(PhoneNumber::try_from(34612345678)?, PhoneNumber::from_str("34 612 345 678")?);
+        Ok(())
+    }
+
+    #[test]
+    fn fixtures_are_valid_and_distinct() {
+        assert_eq!(Ok(()), Person::fixture().validate_all());
+
+        let people = Person::fixtures(3);
+        assert_eq!(vec!["Hector1", "Hector2", "Hector3"], people.iter().map(|p| p.name()).collect::<Vec<_>>());
+        assert_eq!(vec![19, 20, 21], people.iter().map(|p| p.age()).collect::<Vec<_>>());
+        assert!(people.iter().all(|p| p.validate_all().is_ok()));
+        assert!(Person::fixtures(0).is_empty());
+    }
+
+    #[test]
+    fn vcard() -> Result<(), BuilderError> {
+        let person = Person::new(String::from("Hector"), String::from("Alarcon"), 28);
+        let vcard = person.to_vcard();
+        assert_eq!("BEGIN:VCARD\r\nVERSION:3.0\r\nN:Alarcon;Hector;;;\r\nFN:Hector Alarcon\r\nEND:VCARD\r\n", vcard);
+        assert!(!vcard.contains("TEL"));
+
+        let vcard = person.with_phone(612345678)?.to_vcard();
+        assert!(vcard.contains("\r\nTEL:612345678\r\n"));
+        Ok(())
+    }
+
+    #[test]
+    fn vcard_escapes_values() -> Result<(), BuilderError> {
+        let person = Person::new(String::from("Hector; Jr."), String::from("Alarcon, de la\\Vega"), 28)
+            .with_email("hector@example.com")?;
+        let vcard = person.to_vcard();
+        assert!(vcard.contains("\r\nN:Alarcon\\, de la\\\\Vega;Hector\\; Jr.;;;\r\n"));
+        assert!(vcard.contains("\r\nFN:Hector\\; Jr. Alarcon\\, de la\\\\Vega\r\n"));
+        assert!(vcard.contains("\r\nEMAIL:hector@example.com\r\n"));
+
+        // A line break inside a value can't end the line
+        let person = Person::new(String::from("Hector\nBEGIN:VCARD"), String::from("Alarcon\r\n"), 28);
+        let vcard = person.to_vcard();
+        assert!(vcard.contains("\r\nN:Alarcon\\n;Hector\\nBEGIN:VCARD;;;\r\n"));
+        assert_eq!(5, vcard.lines().count());
+        Ok(())
+    }
+
+    #[test]
+    fn with_fields() -> Result<(), BuilderError> {
+        let pairs = vec![
+            (String::from("job_title"), String::from("Software Engineer")),
+            (String::from("nationality"), String::from("Spanish")),
+        ];
+        let person = Person::new(String::from("Hector"), String::from("Alarcon"), 28).with_fields(pairs)?;
+        assert_eq!(Some(String::from("Software Engineer")), person.job_title);
+        assert_eq!(Some("Spanish"), person.nationality.as_deref());
+
+        let pairs = vec![
+            (String::from("job_title"), String::from("Software Engineer")),
+            (String::from("favourite_color"), String::from("Blue")),
+        ];
+        let err = person.with_fields(pairs).unwrap_err();
+        assert_eq!(BuilderError::UnknownField(String::from("favourite_color")), err);
+
+        let pairs = vec![(String::from("email"), String::from("nope"))];
+        let err = Person::new(String::from("Hector"), String::from("Alarcon"), 28).with_fields(pairs).unwrap_err();
+        assert_eq!(BuilderError::InvalidEmail(String::from("nope")), err);
+        Ok(())
+    }
+
+    #[test]
+    fn interned_nationality_is_shared() {
+        let mut pool = NationalityPool::new();
+        let hector = Person::new(String::from("Hector"), String::from("Alarcon"), 28)
+            .with_nationality(pool.intern("Spanish"));
+        let maria = Person::new(String::from("Maria"), String::from("Garcia"), 31)
+            .with_nationality(pool.intern("Spanish"));
+
+        assert!(Rc::ptr_eq(hector.nationality.as_ref().unwrap(), maria.nationality.as_ref().unwrap()));
+        assert_eq!(1, pool.len());
+    }
+
+    #[test]
+    fn json_round_trip() -> Result<(), BuilderError> {
+        let person = Person::new(String::from("Hector"), String::from("Alarcon"), 28)
+            .with_job_title(String::from("Software Engineer"))
+            .with_phone(612345678)?;
+
+        let json = person.to_json().unwrap();
+        assert_eq!(
+            r#"{"name":"Hector","family_name":"Alarcon","age":28,"phone":612345678,"job_title":"Software Engineer"}"#,
+            json
+        );
+        assert_eq!(person, Person::from_json(&json).unwrap());
+        Ok(())
+    }
+
+    #[test]
+    fn from_ndjson_keeps_going_after_a_bad_line() {
+        let input = concat!(
+            r#"{"name":"Hector","family_name":"Alarcon","age":28}"#, "\n",
+            r#"{"name":"Carmen","age":"sixty"}"#, "\n",
+            r#"{"name":"Lucia","family_name":"Alarcon","age":5}"#, "\n",
+        );
+
+        let (people, errors) = person::from_ndjson(input);
+        assert_eq!(vec!["Hector", "Lucia"], people.iter().map(|p| p.name()).collect::<Vec<_>>());
+        assert_eq!(1, errors.len());
+        assert_eq!(1, errors[0].0);
+        assert!(!errors[0].1.is_empty());
+    }
+
+    #[test]
+    fn from_json_validates() {
+        let err = Person::from_json(r#"{"name":"Hector","family_name":"Alarcon","age":200,"email":"nope"}"#).unwrap_err();
+        assert!(err.to_string().contains("invalid age: 200 is above 150"));
+        assert!(err.to_string().contains("invalid email: missing '@'"));
+
+        let err = Person::from_json(r#"{"name":"Hector","family_name":"Alarcon","age":28,"phone":1}"#).unwrap_err();
+        assert!(err.to_string().contains("invalid phone: \"1\""));
+
+        let input = concat!(
+            r#"{"name":"Hector","family_name":"Alarcon","age":28}"#, "\n",
+            r#"{"name":"Carmen","family_name":"Alarcon","age":200}"#, "\n",
+        );
+        let (people, errors) = person::from_ndjson(input);
+        assert_eq!(1, people.len());
+        assert_eq!(vec![(1, String::from("invalid age: 200 is above 150"))], errors);
+    }
+
+    #[test]
+    fn write_ndjson_round_trip() {
+        let people = Person::fixtures(2);
+        let mut buffer: Vec<u8> = Vec::new();
+        person::write_ndjson(&people, &mut buffer).unwrap();
+
+        let output = String::from_utf8(buffer).unwrap();
+        assert!(output.ends_with('\n'));
+        assert_eq!(2, output.lines().count());
+
+        let (parsed, errors) = person::from_ndjson(&output);
+        assert!(errors.is_empty());
+        assert_eq!(people, parsed);
+    }
+
+    #[test]
+    fn from_full_name() -> Result<(), BuilderError> {
+        let person = Person::from_full_name("Hector Alarcon", 28)?;
+        assert_eq!("Hector", person.name);
+        assert_eq!("Alarcon", person.family_name);
+        assert_eq!(28, person.age);
+
+        let person = Person::from_full_name("Maria Jose Garcia", 31)?;
+        assert_eq!("Maria Jose", person.name);
+        assert_eq!("Garcia", person.family_name);
+
+        assert_eq!(
+            BuilderError::InvalidName(String::from("Hector")),
+            Person::from_full_name("Hector", 28).unwrap_err()
+        );
+        Ok(())
+    }
 }
@@ -0,0 +1,146 @@
+use std::collections::BTreeMap;
+
+use crate::error::BuilderError;
+use crate::person::Person;
+
+/// The classic builder: a separate, mutable struct where every field is optional until `build`.
+/// Setters take `&mut self` and return `&mut Self`, like `std::process::Command`, so the same
+/// builder can be kept around, edited and built again.
+///
+/// Besides the hard errors of `build`, setters record soft warnings: values that are valid but
+/// look suspicious. There is at most one per field, setting the field again replaces or clears
+/// it. Reading them with `warnings` is left to whoever shows the form.
+#[allow(dead_code)]
+#[derive(Debug, Default, Clone)]
+pub struct PersonBuilder {
+    name: Option<String>,
+    family_name: Option<String>,
+    age: Option<u8>,
+    phone: Option<u64>,
+    email: Option<String>,
+    home_address: Option<String>,
+    job_title: Option<String>,
+    education: Option<String>,
+    residency: Option<String>,
+    nationality: Option<String>,
+    warnings: BTreeMap<&'static str, String>,
+}
+
+/// A saved copy of a `PersonBuilder`, for undo.
+#[allow(dead_code)]
+#[derive(Debug, Clone)]
+pub struct PersonSnapshot(PersonBuilder);
+
+#[allow(dead_code)]
+impl PersonBuilder {
+    // Valid, but most real numbers have more digits than this, or are older than this.
+    const SHORT_PHONE_DIGITS: usize = 9;
+    const OLD_AGE: u8 = 110;
+
+    pub fn new() -> Self { Self::default() }
+
+    pub fn name(&mut self, name: impl Into<String>) -> &mut Self { self.name = Some(name.into()); self }
+    pub fn family_name(&mut self, family_name: impl Into<String>) -> &mut Self { self.family_name = Some(family_name.into()); self }
+    pub fn age(&mut self, age: u8) -> &mut Self {
+        self.warn_if(age > Self::OLD_AGE, "age", || format!("age {age} looks too high"));
+        self.age = Some(age);
+        self
+    }
+    /// Kept as plain digits, `build` turns them into a `PhoneNumber` (or an error).
+    pub fn phone(&mut self, phone: u64) -> &mut Self {
+        self.warn_if(phone.to_string().len() < Self::SHORT_PHONE_DIGITS, "phone", || {
+            format!("phone {phone} looks too short")
+        });
+        self.phone = Some(phone);
+        self
+    }
+    pub fn email(&mut self, email: impl Into<String>) -> &mut Self { self.email = Some(email.into()); self }
+    pub fn home_address(&mut self, home_address: impl Into<String>) -> &mut Self { self.home_address = Some(home_address.into()); self }
+    pub fn job_title(&mut self, job_title: impl Into<String>) -> &mut Self { self.job_title = Some(job_title.into()); self }
+    pub fn education(&mut self, education: impl Into<String>) -> &mut Self { self.education = Some(education.into()); self }
+    pub fn residency(&mut self, residency: impl Into<String>) -> &mut Self { self.residency = Some(residency.into()); self }
+    pub fn nationality(&mut self, nationality: impl Into<String>) -> &mut Self { self.nationality = Some(nationality.into()); self }
+
+    /// The warnings for the current values, ordered by field name. They never stop `build`.
+    pub fn warnings(&self) -> Vec<&str> { self.warnings.values().map(String::as_str).collect() }
+
+    /// Replaces the warning of `field`, or clears it when the new value looks fine.
+    fn warn_if(&mut self, suspicious: bool, field: &'static str, message: impl FnOnce() -> String) {
+        if suspicious {
+            self.warnings.insert(field, message());
+        } else {
+            self.warnings.remove(field);
+        }
+    }
+
+    pub fn snapshot(&self) -> PersonSnapshot { PersonSnapshot(self.clone()) }
+    pub fn restore(&mut self, snap: PersonSnapshot) { *self = snap.0; }
+
+    /// Checks the required fields are there, then goes through the validating `with_..` setters
+    /// of `Person`.
+    pub fn build(&self) -> Result<Person, BuilderError> {
+        let name = self.name.clone().ok_or(BuilderError::MissingField("name"))?;
+        let family_name = self.family_name.clone().ok_or(BuilderError::MissingField("family_name"))?;
+        let age = self.age.ok_or(BuilderError::MissingField("age"))?;
+
+        let mut person = Person::try_new(name, family_name, age)?;
+        if let Some(phone) = self.phone { person = person.with_phone(phone)?; }
+        if let Some(email) = self.email.clone() { person = person.with_email(email)?; }
+        if let Some(home_address) = self.home_address.clone() { person = person.with_home_address(home_address); }
+        if let Some(job_title) = self.job_title.clone() { person = person.with_job_title(job_title); }
+        if let Some(education) = self.education.clone() { person = person.with_education(education); }
+        if let Some(residency) = self.residency.clone() { person = person.with_residency(residency); }
+        if let Some(nationality) = self.nationality.clone() { person = person.with_nationality(nationality); }
+
+        Ok(person)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::error::BuilderError;
+    use crate::person_builder::PersonBuilder;
+
+    #[test]
+    fn snapshot_and_restore() {
+        let mut builder = PersonBuilder::new();
+        builder.name("Hector").family_name("Alarcon").age(28).job_title("Software Engineer");
+
+        let snapshot = builder.snapshot();
+        builder.job_title("Staff Engineer");
+        assert_eq!(Some(String::from("Staff Engineer")), builder.job_title);
+
+        builder.restore(snapshot);
+        assert_eq!(Some(String::from("Software Engineer")), builder.job_title);
+    }
+
+    #[test]
+    fn build_requires_required_fields() {
+        let mut builder = PersonBuilder::new();
+        builder.name("Hector").family_name("Alarcon");
+        assert_eq!(BuilderError::MissingField("age"), builder.build().unwrap_err());
+
+        builder.age(28);
+        assert!(builder.build().is_ok());
+    }
+
+    #[test]
+    fn warnings_do_not_fail_the_build() {
+        let mut builder = PersonBuilder::new();
+        builder.name("Hector").family_name("Alarcon").age(28).phone(12345u64);
+
+        let person = builder.build();
+        assert!(person.is_ok());
+        assert_eq!(vec!["phone 12345 looks too short"], builder.warnings());
+
+        builder.age(120);
+        assert!(builder.build().is_ok());
+        assert_eq!(vec!["age 120 looks too high", "phone 12345 looks too short"], builder.warnings());
+
+        // Fixing a value takes its warning away
+        builder.phone(34600000000u64);
+        assert_eq!(vec!["age 120 looks too high"], builder.warnings());
+        builder.age(28);
+        assert!(builder.warnings().is_empty());
+    }
+}
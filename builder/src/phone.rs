@@ -0,0 +1,84 @@
+use std::fmt;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::BuilderError;
+
+/// A phone number stored as its digits, e.g. `+34 600 000 000` is `34600000000`. The only ways
+/// in are `TryFrom<u64>` and `FromStr`, and both check the length, so every `PhoneNumber` is valid.
+/// Deserializing goes through `TryFrom` too: serde gives us a `u64` and we turn it into a phone.
+#[allow(dead_code)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(try_from = "u64", into = "u64")]
+pub struct PhoneNumber(u64);
+
+#[allow(dead_code)]
+impl PhoneNumber {
+    // E.164 numbers have at most 15 digits. Anything below 5 is not a phone number.
+    const MIN_DIGITS: usize = 5;
+    const MAX_DIGITS: usize = 15;
+
+    pub fn digits(&self) -> u64 { self.0 }
+
+    /// The digits in groups of three counted from the right, so the odd group is the leading
+    /// one: `612 345 678`, `34 612 345 678`, `12 345`. `FromStr` reads it back.
+    pub fn grouped(&self) -> String {
+        let digits = self.0.to_string();
+        let first = match digits.len() % 3 { 0 => 3, rest => rest };
+
+        let mut groups = vec![&digits[..first]];
+        let mut rest = &digits[first..];
+        while !rest.is_empty() {
+            let (group, tail) = rest.split_at(3);
+            groups.push(group);
+            rest = tail;
+        }
+        groups.join(" ")
+    }
+}
+
+impl fmt::Display for PhoneNumber {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl TryFrom<u64> for PhoneNumber {
+    type Error = BuilderError;
+
+    fn try_from(digits: u64) -> Result<Self, Self::Error> {
+        let len = digits.to_string().len();
+        if (Self::MIN_DIGITS..=Self::MAX_DIGITS).contains(&len) {
+            Ok(Self(digits))
+        } else {
+            Err(BuilderError::InvalidPhone(digits.to_string()))
+        }
+    }
+}
+
+impl From<PhoneNumber> for u64 {
+    fn from(phone: PhoneNumber) -> Self { phone.0 }
+}
+
+impl FromStr for PhoneNumber {
+    type Err = BuilderError;
+
+    /// Accepts an optional leading `+` and spaces or dashes between the digits.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || BuilderError::InvalidPhone(s.to_string());
+
+        let digits: String = s
+            .trim()
+            .trim_start_matches('+')
+            .chars()
+            .filter(|c| *c != ' ' && *c != '-')
+            .collect();
+
+        if digits.is_empty() || !digits.chars().all(|c| c.is_ascii_digit()) {
+            return Err(invalid());
+        }
+
+        Self::try_from(digits.parse::<u64>().map_err(|_| invalid())?)
+    }
+}
@@ -0,0 +1,27 @@
+{
+  description = "DevShell for Rust";
+  inputs = {
+    nixpkgs.url = github:nixos/nixpkgs/nixos-unstable;
+    rust-overlay.url = github:oxalica/rust-overlay;
+    flake-utils.url = github:numtide/flake-utils;
+  };
+  outputs = {
+    self,
+    nixpkgs,
+    rust-overlay,
+    flake-utils,
+  }:
+    flake-utils.lib.eachDefaultSystem (
+      system: let
+        overlays = [(import rust-overlay)];
+        pkgs = import nixpkgs {inherit overlays system;};
+      in {
+        devShells.default = pkgs.mkShell {
+          packages = with pkgs; [
+            pkg-config
+            (rust-bin.fromRustupToolchainFile ./rust-toolchain.toml)
+          ];
+        };
+      }
+    );
+}
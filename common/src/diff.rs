@@ -0,0 +1,75 @@
+use std::fmt;
+
+/// A single field that differs between two values of the same type.
+#[allow(dead_code)]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FieldChange {
+    pub field: &'static str,
+    pub from: String,
+    pub to: String,
+}
+
+/// Anything that can list how it changed, field by field. Audit code only needs this trait, not
+/// the type behind it: the same function takes a `Person` from the builder crate or a `Config`
+/// from the block-pattern one.
+pub trait Diffable {
+    /// Every field that changed from `self` to `other`, in declaration order.
+    fn diff(&self, other: &Self) -> Vec<FieldChange>;
+}
+
+/// Collects the `FieldChange`s of a `Diffable` impl, skipping the fields that are equal.
+#[derive(Debug, Default)]
+pub struct Changes(Vec<FieldChange>);
+
+impl Changes {
+    pub fn compare(&mut self, field: &'static str, from: String, to: String) {
+        if from != to {
+            self.0.push(FieldChange { field, from, to });
+        }
+    }
+
+    pub fn into_vec(self) -> Vec<FieldChange> {
+        self.0
+    }
+}
+
+/// Unset optional fields are reported as an empty string.
+pub fn describe<T: fmt::Display>(value: &Option<T>) -> String {
+    value.as_ref().map(|v| v.to_string()).unwrap_or_default()
+}
+
+/// How many fields differ, for any `Diffable`.
+pub fn count_changes<T: Diffable>(a: &T, b: &T) -> usize {
+    a.diff(b).len()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::diff::{count_changes, describe, Changes, Diffable, FieldChange};
+
+    struct Point {
+        x: i32,
+        label: Option<&'static str>,
+    }
+
+    impl Diffable for Point {
+        fn diff(&self, other: &Point) -> Vec<FieldChange> {
+            let mut changes = Changes::default();
+            changes.compare("x", self.x.to_string(), other.x.to_string());
+            changes.compare("label", describe(&self.label), describe(&other.label));
+            changes.into_vec()
+        }
+    }
+
+    #[test]
+    fn only_changed_fields() {
+        let a = Point { x: 1, label: None };
+        let b = Point { x: 1, label: Some("origin") };
+
+        assert_eq!(
+            vec![FieldChange { field: "label", from: String::new(), to: String::from("origin") }],
+            a.diff(&b)
+        );
+        assert_eq!(0, count_changes(&a, &a));
+    }
+}
@@ -0,0 +1,10 @@
+// Pieces more than one pattern crate needs. Each crate is still its own project, they depend on
+// this one by path (`common = { path = "../common" }`) instead of keeping copies that drift apart.
+
+pub mod diff;
+mod macros;
+
+// `optional_builder!` expands in the crate that calls it, where `paste` may not be a dependency.
+// It reaches it through here instead.
+#[doc(hidden)]
+pub use paste;
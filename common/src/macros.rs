@@ -0,0 +1,137 @@
+/// Wraps a struct definition and writes the boring `with_..` setters of a fluent builder: one per
+/// `Option<T>` field, taking `impl Into<T>` and wrapping it in `Some`. Fields that aren't an
+/// `Option` get no setter. Mark a field with `#[no_setter]` when its setter validates and is
+/// written by hand, the marker is removed from the struct.
+///
+/// ```Rust
+/// use common::optional_builder;
+///
+/// optional_builder! {
+///     pub struct Person {
+///         name: String,
+///         #[no_setter]
+///         email: Option<String>,
+///         job_title: Option<String>,
+///         nationality: Option<Rc<str>>,
+///     }
+/// }
+/// // Person::new(..).with_job_title("Software Engineer").with_nationality("Spanish")
+/// ```
+///
+/// The setters have the visibility of the struct. `macro_rules!` can't tell a type is an `Option`
+/// once it has parsed it as a `ty`, so the fields are munched one by one and the raw tokens are
+/// matched against `Option<..>` first. It can't glue `with_` and the field name into a new
+/// identifier on its own either, the `paste` crate does that with `[<with_ $field>]`.
+#[macro_export]
+macro_rules! optional_builder {
+    (
+        $(#[$attr:meta])*
+        $vis:vis struct $name:ident { $($body:tt)* }
+    ) => {
+        $crate::optional_builder!(@munch {
+            attrs: [$(#[$attr])*], vis: [$vis], name: $name,
+            fields: [], setters: [], field_attrs: [], skip: false,
+        } $($body)*);
+    };
+
+    // Every field munched: the struct as written, minus the markers, and its setters.
+    (@munch {
+        attrs: [$($attr:tt)*], vis: [$vis:vis], name: $name:ident,
+        fields: [$($fields:tt)*], setters: [$([$field:ident $inner:ty])*], field_attrs: [], skip: false,
+    }) => {
+        $($attr)*
+        $vis struct $name { $($fields)* }
+
+        $crate::paste::paste! {
+            // `..self` is needless when the struct has a single field
+            #[allow(dead_code, clippy::needless_update)]
+            impl $name {
+                $(
+                    #[doc = concat!("Sets `", stringify!($field), "`.")]
+                    $vis fn [<with_ $field>](self, $field: impl Into<$inner>) -> Self {
+                        Self { $field: Some($field.into()), ..self }
+                    }
+                )*
+            }
+        }
+    };
+
+    (@munch {
+        attrs: $attrs:tt, vis: $vis:tt, name: $name:ident,
+        fields: $fields:tt, setters: $setters:tt, field_attrs: $field_attrs:tt, skip: $skip:tt,
+    } #[no_setter] $($rest:tt)*) => {
+        $crate::optional_builder!(@munch {
+            attrs: $attrs, vis: $vis, name: $name,
+            fields: $fields, setters: $setters, field_attrs: $field_attrs, skip: true,
+        } $($rest)*);
+    };
+
+    (@munch {
+        attrs: $attrs:tt, vis: $vis:tt, name: $name:ident,
+        fields: $fields:tt, setters: $setters:tt, field_attrs: [$($field_attr:tt)*], skip: $skip:tt,
+    } #[$meta:meta] $($rest:tt)*) => {
+        $crate::optional_builder!(@munch {
+            attrs: $attrs, vis: $vis, name: $name,
+            fields: $fields, setters: $setters, field_attrs: [$($field_attr)* #[$meta]], skip: $skip,
+        } $($rest)*);
+    };
+
+    // An `Option` field without the marker gets a setter.
+    (@munch {
+        attrs: $attrs:tt, vis: $vis:tt, name: $name:ident,
+        fields: [$($fields:tt)*], setters: [$($setters:tt)*], field_attrs: [$($field_attr:tt)*], skip: false,
+    } $field_vis:vis $field:ident : Option<$inner:ty> $(, $($rest:tt)*)?) => {
+        $crate::optional_builder!(@munch {
+            attrs: $attrs, vis: $vis, name: $name,
+            fields: [$($fields)* $($field_attr)* $field_vis $field: Option<$inner>,],
+            setters: [$($setters)* [$field $inner]], field_attrs: [], skip: false,
+        } $($($rest)*)?);
+    };
+
+    // Any other field is only copied.
+    (@munch {
+        attrs: $attrs:tt, vis: $vis:tt, name: $name:ident,
+        fields: [$($fields:tt)*], setters: $setters:tt, field_attrs: [$($field_attr:tt)*], skip: $skip:tt,
+    } $field_vis:vis $field:ident : $ty:ty $(, $($rest:tt)*)?) => {
+        $crate::optional_builder!(@munch {
+            attrs: $attrs, vis: $vis, name: $name,
+            fields: [$($fields)* $($field_attr)* $field_vis $field: $ty,],
+            setters: $setters, field_attrs: [], skip: false,
+        } $($($rest)*)?);
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use std::rc::Rc;
+
+    crate::optional_builder! {
+        #[derive(Debug, Default, PartialEq)]
+        struct Contact {
+            /// Required, no setter.
+            name: String,
+            #[no_setter]
+            email: Option<String>,
+            nickname: Option<String>,
+            city: Option<Rc<str>>,
+        }
+    }
+
+    impl Contact {
+        fn with_email(self, email: &str) -> Option<Self> {
+            email.contains('@').then(|| Self { email: Some(email.to_string()), ..self })
+        }
+    }
+
+    #[test]
+    fn setters_for_option_fields() {
+        let contact = Contact::default().with_nickname("Pepe").with_city("Madrid");
+        assert_eq!(Some(String::from("Pepe")), contact.nickname);
+        assert_eq!(Some(Rc::from("Madrid")), contact.city);
+        // contact.with_name("Jose"); // ERROR! `name` is not an `Option`, it has no setter
+
+        // The hand-written setter, the macro left `email` alone
+        assert_eq!(None, Contact::default().with_email("nope"));
+        assert_eq!(Some(String::from("x@y.z")), Contact::default().with_email("x@y.z").unwrap().email);
+    }
+}
@@ -0,0 +1,44 @@
+// Unlike `Clamp`, here a real blanket impl works: every number that converts to `f64` without
+// losing precision (`i32`, `u8`, `f32`, `f64`...) implements `Into<f64>`, so one impl for `[T]`
+// covers them all. `i64` doesn't, since not every `i64` fits in an `f64`.
+#[allow(unused)]
+pub trait Average {
+    fn average(&self) -> Option<f64>;
+}
+
+impl<T: Copy + Into<f64>> Average for [T] {
+    // Widening first means adding many `i32` can't overflow. An empty slice has no average.
+    fn average(&self) -> Option<f64> {
+        if self.is_empty() {
+            return None;
+        }
+        let sum: f64 = self.iter().map(|n| (*n).into()).sum();
+        Some(sum / self.len() as f64)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::average::Average;
+
+    #[test]
+    fn integers() {
+        assert_eq!(Some(2.5), [1, 2, 3, 4].average());
+        assert_eq!(Some(i32::MAX as f64), [i32::MAX, i32::MAX].average());
+        let numbers: Vec<i32> = (-3..=1).collect();
+        assert_eq!(Some(-1.0), numbers.average());
+    }
+
+    #[test]
+    fn floats() {
+        assert_eq!(Some(0.5), [0.25, 0.75].average());
+        assert_eq!(Some(3.0), [3.0f64].average());
+    }
+
+    #[test]
+    fn empty() {
+        let empty: [f64; 0] = [];
+        assert_eq!(None, empty.average());
+        assert_eq!(None, Vec::<i32>::new().average());
+    }
+}
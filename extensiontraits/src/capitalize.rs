@@ -0,0 +1,38 @@
+// Only the first character goes up, the rest stays as it is: "hello WORLD" is "Hello WORLD".
+// That's what you want for the start of a sentence, title case would touch every word.
+#[allow(unused)]
+pub trait Capitalize {
+    fn capitalize_first(&self) -> String;
+}
+
+impl Capitalize for str {
+    fn capitalize_first(&self) -> String {
+        let mut chars = self.chars();
+        match chars.next() {
+            // `to_uppercase` gives an iterator: some characters become more than one, like `ß`.
+            Some(first) => first.to_uppercase().chain(chars).collect(),
+            None => String::new(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::capitalize::Capitalize;
+
+    #[test]
+    fn capitalizes_first_letter() {
+        assert_eq!("Hello world", "hello world".capitalize_first());
+        assert_eq!("Hello WORLD", "hello WORLD".capitalize_first());
+        assert_eq!("Hello", "Hello".capitalize_first());
+        assert_eq!("Ángel", "ángel".capitalize_first());
+        assert_eq!("", "".capitalize_first());
+    }
+
+    #[test]
+    fn leading_non_letter() {
+        assert_eq!("¡hola!", "¡hola!".capitalize_first());
+        assert_eq!("42 apples", "42 apples".capitalize_first());
+        assert_eq!(" hello", " hello".capitalize_first());
+    }
+}
@@ -0,0 +1,52 @@
+use std::collections::HashMap;
+
+// How many times each character shows up. The classic first step of a frequency analysis, or of
+// Huffman coding.
+#[allow(unused)]
+pub trait CharFreq {
+    fn char_frequency(&self) -> HashMap<char, usize>;
+    fn most_common_char(&self) -> Option<char>;
+}
+
+impl CharFreq for str {
+    fn char_frequency(&self) -> HashMap<char, usize> {
+        let mut freq = HashMap::new();
+        for c in self.chars() {
+            *freq.entry(c).or_insert(0) += 1;
+        }
+        freq
+    }
+
+    // A `HashMap` has no order, so a tie needs an explicit rule: the smallest `char` wins.
+    // Comparing `(count, Reverse(char))` makes `max_by_key` do both at once.
+    fn most_common_char(&self) -> Option<char> {
+        self.char_frequency()
+            .into_iter()
+            .max_by_key(|(c, count)| (*count, std::cmp::Reverse(*c)))
+            .map(|(c, _)| c)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::char_freq::CharFreq;
+
+    #[test]
+    fn counts_characters() {
+        let freq = "hello".char_frequency();
+        assert_eq!(Some(&2), freq.get(&'l'));
+        assert_eq!(Some(&1), freq.get(&'h'));
+        assert_eq!(None, freq.get(&'z'));
+        assert_eq!(4, freq.len());
+        assert!("".char_frequency().is_empty());
+    }
+
+    #[test]
+    fn most_common() {
+        assert_eq!(Some('l'), "hello".most_common_char());
+        // Every character once: the smallest wins
+        assert_eq!(Some('a'), "cab".most_common_char());
+        assert_eq!(Some(' '), "a b c".most_common_char());
+        assert_eq!(None, "".most_common_char());
+    }
+}
@@ -0,0 +1,41 @@
+// This is how crates like `itertools` work: a trait with a blanket implementation for every
+// `Iterator`. Import the trait and every iterator in your codebase gets the new adaptor.
+#[allow(unused)]
+pub trait ChunkedExt: Iterator + Sized {
+    fn chunked(self, size: usize) -> impl Iterator<Item = Vec<Self::Item>>;
+}
+
+impl<I: Iterator> ChunkedExt for I {
+    // Groups the items in vectors of `size`. The last one gets whatever is left.
+    fn chunked(mut self, size: usize) -> impl Iterator<Item = Vec<Self::Item>> {
+        assert!(size > 0, "chunked: the chunk size must be greater than zero");
+
+        std::iter::from_fn(move || {
+            let chunk: Vec<Self::Item> = self.by_ref().take(size).collect();
+            if chunk.is_empty() { None } else { Some(chunk) }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::chunked::ChunkedExt;
+
+    #[test]
+    fn chunks_with_smaller_last_one() {
+        let chunks: Vec<Vec<i32>> = (0..7).chunked(3).collect();
+        assert_eq!(vec![vec![0, 1, 2], vec![3, 4, 5], vec![6]], chunks);
+    }
+
+    #[test]
+    fn chunks_exact_and_empty() {
+        assert_eq!(vec![vec![0, 1], vec![2, 3]], (0..4).chunked(2).collect::<Vec<_>>());
+        assert!((0..0).chunked(2).next().is_none());
+    }
+
+    #[test]
+    #[should_panic(expected = "chunk size must be greater than zero")]
+    fn zero_size_panics() {
+        let _ = (0..7).chunked(0);
+    }
+}
@@ -0,0 +1,63 @@
+// The standard library already has `Ord::clamp`, but it panics when `min > max` and it doesn't
+// exist for `f64` (there is `f64::clamp`, which panics too). Let's write our own that never panics.
+#[allow(unused)]
+pub trait Clamp {
+    fn clamp_to(&self, min: Self, max: Self) -> Self;
+}
+
+// The implementation is identical for every number type, so instead of copy pasting it we let a
+// macro write each `impl` for us. It's not a real blanket impl (`impl<T> Clamp for T`), but it
+// reads almost like one.
+macro_rules! impl_clamp {
+    ($($t:ty),*) => {
+        $(
+            impl Clamp for $t {
+                fn clamp_to(&self, min: Self, max: Self) -> Self {
+                    // A range that ends before it begins is degenerate: `min` wins.
+                    if min > max || *self < min {
+                        min
+                    } else if *self > max {
+                        max
+                    } else {
+                        *self
+                    }
+                }
+            }
+        )*
+    };
+}
+
+impl_clamp!(i32, u8, f64);
+
+#[cfg(test)]
+mod tests {
+    use crate::clamp::Clamp;
+
+    #[test]
+    fn clamp_i32() {
+        assert_eq!(0, (-5).clamp_to(0, 10));
+        assert_eq!(5, 5.clamp_to(0, 10));
+        assert_eq!(10, 15.clamp_to(0, 10));
+    }
+
+    #[test]
+    fn clamp_u8() {
+        assert_eq!(10, 3u8.clamp_to(10, 20));
+        assert_eq!(15, 15u8.clamp_to(10, 20));
+        assert_eq!(20, 255u8.clamp_to(10, 20));
+    }
+
+    #[test]
+    fn clamp_f64() {
+        assert_eq!(0.0, (-0.5f64).clamp_to(0.0, 1.0));
+        assert_eq!(0.5, 0.5f64.clamp_to(0.0, 1.0));
+        assert_eq!(1.0, 1.5f64.clamp_to(0.0, 1.0));
+    }
+
+    #[test]
+    fn clamp_min_greater_than_max() {
+        assert_eq!(10, 5.clamp_to(10, 0));
+        assert_eq!(10u8, 200u8.clamp_to(10, 0));
+        assert_eq!(1.0, 0.5f64.clamp_to(1.0, 0.0));
+    }
+}
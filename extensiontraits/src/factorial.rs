@@ -15,8 +15,31 @@
 // had it. Duh!
 
 // First, let's define the trait
+/// `n!` for the integer types, with nothing but `core`:
+///
+/// ```
+/// use extensiontraits::factorial::Factorial;
+///
+/// assert_eq!(10u64.factorial(), 3_628_800);
+/// assert_eq!(u64::product_range(3, 5), Some(60));
+/// assert_eq!(u64::factorial_inverse(120), Some(5));
+/// ```
+///
+/// Also run with `cargo test --doc --no-default-features`, where the library is built without
+/// `std`.
+#[allow(unused)]
 pub trait Factorial {
     fn factorial(&self) -> Self;
+    // A factorial is the product of `1..=n`. This is the product of any range, `None` if it
+    // overflows. It has no `self`, so it's called on the type: `i32::product_range(3, 5)`.
+    fn product_range(start: Self, end: Self) -> Option<Self>
+    where
+        Self: Sized;
+    // The other way around: the `n` with `n! == value`, `None` if there is none.
+    // Both 0! and 1! are 1, we answer 1. Also called on the type: `i32::factorial_inverse(120)`.
+    fn factorial_inverse(value: Self) -> Option<Self>
+    where
+        Self: Sized;
 }
 
 impl Factorial for i32 {
@@ -29,10 +52,193 @@ impl Factorial for i32 {
         }
         res
     }
+
+    // An empty range (`start > end`) multiplies nothing, which is 1.
+    fn product_range(start: Self, end: Self) -> Option<Self> {
+        (start..=end).try_fold(1, |acc: Self, n| acc.checked_mul(n))
+    }
+
+    // Undo the factorial by dividing by 2, 3, 4... until we reach 1. If some division is not
+    // exact, `value` was never a factorial.
+    fn factorial_inverse(value: Self) -> Option<Self> {
+        if value < 1 {
+            return None;
+        }
+        let mut n = 1;
+        let mut rest = value;
+        while rest > 1 {
+            n += 1;
+            if rest % n != 0 {
+                return None;
+            }
+            rest /= n;
+        }
+        Some(n)
+    }
+}
+
+// Nothing above needs the standard library, only `core` arithmetic. The same goes for every
+// integer, so a macro writes the rest of the impls. The module is the `#![no_std]` library of
+// this crate (see `lib.rs`), anything below that needs `std` only exists with the `std` feature.
+macro_rules! impl_factorial {
+    ($($t:ty),*) => {
+        $(
+            impl Factorial for $t {
+                fn factorial(&self) -> Self {
+                    (1..=*self).product()
+                }
+
+                fn product_range(start: Self, end: Self) -> Option<Self> {
+                    (start..=end).try_fold(1, |acc: Self, n| acc.checked_mul(n))
+                }
+
+                fn factorial_inverse(value: Self) -> Option<Self> {
+                    if value < 1 {
+                        return None;
+                    }
+                    let mut n: Self = 1;
+                    let mut rest = value;
+                    while rest > 1 {
+                        n += 1;
+                        if rest % n != 0 {
+                            return None;
+                        }
+                        rest /= n;
+                    }
+                    Some(n)
+                }
+            }
+        )*
+    };
+}
+
+impl_factorial!(u8, u16, u32, u64, u128, i64);
+
+// The `i32` factorial above silently overflows past 12!. When we don't know the input beforehand
+// it's better to ask for the result and let the type tell us if it fits.
+#[allow(dead_code)]
+pub fn checked_factorial(n: u32) -> Option<u64> {
+    (1..=n as u64).try_fold(1u64, |acc, k| acc.checked_mul(k))
+}
+
+// `1! + 2! + ... + n!`. Both the factorials and the running sum can overflow, and `None` from
+// either one stops the whole computation.
+#[allow(dead_code)]
+pub fn sum_of_factorials(n: u32) -> Option<u64> {
+    (1..=n).try_fold(0u64, |sum, k| sum.checked_add(checked_factorial(k)?))
+}
+
+// `n! mod modulus`, reducing after every multiplication so the value never grows past the
+// modulus. The multiplication itself happens in `u128`, where two `u64` always fit. Once `n`
+// reaches the modulus, the modulus is one of the factors and the answer is 0 without any loop.
+#[allow(dead_code)]
+pub fn factorial_mod(n: u64, modulus: u64) -> u64 {
+    assert!(modulus != 0, "factorial_mod: the modulus must not be zero");
+    if n >= modulus {
+        return 0;
+    }
+
+    let modulus = modulus as u128;
+    (1..=n as u128).fold(1 % modulus, |acc, k| acc * (k % modulus) % modulus) as u64
+}
+
+// `n! / m!` cancels everything up to `m!`, leaving `(m + 1) * ... * n`. That's just a
+// `product_range`, and it fits in a `u64` long after `n!` itself stopped fitting.
+#[allow(dead_code)]
+pub fn factorial_ratio(n: u64, m: u64) -> Option<u64> {
+    if m > n {
+        return None;
+    }
+    if m == n {
+        return Some(1);
+    }
+    u64::product_range(m + 1, n)
+}
+
+// Ordered ways to pick `r` items out of `n`: `P(n, r) = n! / (n - r)!`, which is the
+// `factorial_ratio` above. Picking more items than there are is `None`, like an overflow.
+#[allow(dead_code)]
+pub fn permutations_count(n: u64, r: u64) -> Option<u64> {
+    factorial_ratio(n, n.checked_sub(r)?)
+}
+
+// A `const fn` can run at compile time, so tables of factorials cost nothing at runtime:
+// `const F5: u64 = factorial_const(5);`. Iterators and closures aren't allowed in there yet, hence
+// the old-school `while` loop. On overflow it panics, which in a `const` means it doesn't compile.
+#[allow(dead_code)]
+pub const fn factorial_const(n: u32) -> u64 {
+    let mut acc: u64 = 1;
+    let mut k: u64 = 2;
+    while k <= n as u64 {
+        acc = match acc.checked_mul(k) {
+            Some(product) => product,
+            None => panic!("factorial_const: the factorial overflows a u64"),
+        };
+        k += 1;
+    }
+    acc
+}
+
+// Every factorial is the previous one times the next number, so a sequence is a single
+// multiplication per item. It ends by itself when the next one doesn't fit in a `u64`, which
+// makes `FactorialSeq::new().collect()` a complete lookup table.
+#[allow(dead_code)]
+pub struct FactorialSeq {
+    next: Option<u64>,
+    n: u64,
+}
+
+#[allow(dead_code)]
+impl FactorialSeq {
+    pub fn new() -> Self {
+        Self { next: Some(1), n: 0 }
+    }
+}
+
+impl Default for FactorialSeq {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Iterator for FactorialSeq {
+    type Item = u64;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let current = self.next?;
+        self.n += 1;
+        self.next = current.checked_mul(self.n);
+        Some(current)
+    }
+}
+
+// 100! has 158 digits, no primitive integer comes close. `num-bigint` grows as needed, so the
+// factorial is exact and we can add up its digits. It allocates, so it needs `std`.
+#[cfg(feature = "std")]
+#[allow(dead_code)]
+pub fn factorial_digit_sum(n: u32) -> u64 {
+    use num_bigint::BigUint;
+
+    let factorial: BigUint = (1..=n).map(BigUint::from).product();
+    factorial.to_radix_le(10).iter().map(|digit| u64::from(*digit)).sum()
+}
+
+// Computing many factorials is embarrassingly parallel: every input is independent.
+// `rayon` splits the slice across threads and `collect` keeps the original order.
+#[cfg(feature = "parallel")]
+#[allow(dead_code)]
+pub fn factorials_parallel(inputs: &[u32]) -> std::vec::Vec<Option<u64>> {
+    use rayon::prelude::*;
+
+    inputs.par_iter().map(|n| checked_factorial(*n)).collect()
 }
 
 #[cfg(test)]
 mod tests {
+    // The library is `#![no_std]`, the tests bring `std` back in
+    use std::vec;
+    use std::vec::Vec;
+
     // Once it is imported, everyone can use it!
     use crate::factorial::Factorial;
 
@@ -55,5 +261,170 @@ mod tests {
             assert_eq!(3_628_800, n.factorial());
         }
     }
+
+    #[test]
+    fn does_it_work_u64() {
+        assert_eq!(3_628_800, 10u64.factorial());
+        assert_eq!(1, 0u64.factorial());
+        assert_eq!(2_432_902_008_176_640_000, 20u64.factorial());
+        assert_eq!(120, 5u8.factorial());
+    }
+
+    #[test]
+    fn product_range() {
+        assert_eq!(Some(60), i32::product_range(3, 5));
+        assert_eq!(Some(1), i32::product_range(5, 3));
+        assert_eq!(Some(10.factorial()), i32::product_range(1, 10));
+        assert_eq!(None, i32::product_range(1, 13));
+        assert_eq!(Some(60), u64::product_range(3, 5));
+        assert_eq!(None, u8::product_range(10, 20));
+    }
+
+    #[test]
+    fn factorial_inverse() {
+        assert_eq!(Some(5), i32::factorial_inverse(120));
+        assert_eq!(Some(1), i32::factorial_inverse(1));
+        assert_eq!(Some(2), i32::factorial_inverse(2));
+        assert_eq!(Some(12), i32::factorial_inverse(12.factorial()));
+        assert_eq!(None, i32::factorial_inverse(121));
+        assert_eq!(None, i32::factorial_inverse(0));
+        assert_eq!(None, i32::factorial_inverse(-6));
+        assert_eq!(None, i32::factorial_inverse(12));
+
+        assert_eq!(Some(20), u64::factorial_inverse(20u64.factorial()));
+        assert_eq!(None, u64::factorial_inverse(20u64.factorial() + 1));
+        assert_eq!(Some(5), u8::factorial_inverse(120));
+        assert_eq!(None, u8::factorial_inverse(0));
+    }
+
+    #[test]
+    fn factorial_ratio() {
+        use crate::factorial::factorial_ratio;
+
+        assert_eq!(Some(20), factorial_ratio(5, 3));
+        assert_eq!(Some(1), factorial_ratio(3, 3));
+        assert_eq!(Some(120), factorial_ratio(5, 0));
+        assert_eq!(None, factorial_ratio(3, 5));
+        // 100! overflows, 100! / 98! doesn't
+        assert_eq!(Some(9_900), factorial_ratio(100, 98));
+        assert_eq!(Some(1), factorial_ratio(u64::MAX, u64::MAX));
+        assert_eq!(None, factorial_ratio(100, 10));
+    }
+
+    #[test]
+    fn permutations_count() {
+        use crate::factorial::permutations_count;
+
+        assert_eq!(Some(20), permutations_count(5, 2));
+        assert_eq!(Some(1), permutations_count(4, 0));
+        assert_eq!(Some(24), permutations_count(4, 4));
+        assert_eq!(None, permutations_count(3, 5));
+        // 1_000_000 * 999_999 fits, the factorials never had to
+        assert_eq!(Some(999_999_000_000), permutations_count(1_000_000, 2));
+        assert_eq!(None, permutations_count(100, 50));
+    }
+
+    #[test]
+    fn sum_of_factorials() {
+        use crate::factorial::sum_of_factorials;
+
+        assert_eq!(Some(9), sum_of_factorials(3));
+        assert_eq!(Some(1), sum_of_factorials(1));
+        assert_eq!(Some(0), sum_of_factorials(0));
+        assert_eq!(None, sum_of_factorials(21));
+        assert_eq!(None, sum_of_factorials(100));
+    }
+
+    #[test]
+    fn factorial_mod() {
+        use crate::factorial::factorial_mod;
+
+        assert_eq!(120, factorial_mod(5, 1000));
+        // 10! = 3_628_800 = 13 * 279_138 + 6
+        assert_eq!(6, factorial_mod(10, 13));
+        assert_eq!(1, factorial_mod(0, 7));
+        assert_eq!(0, factorial_mod(0, 1));
+        // Wilson's theorem: (p - 1)! = p - 1 (mod p)
+        assert_eq!(10_006, factorial_mod(10_006, 10_007));
+        // Big moduli don't overflow the intermediate products
+        assert_eq!(2_432_902_008_176_640_000, factorial_mod(20, u64::MAX));
+        // `n >= modulus` returns straight away instead of looping `n` times
+        assert_eq!(0, factorial_mod(u64::MAX, 1000));
+        assert_eq!(0, factorial_mod(13, 13));
+    }
+
+    #[test]
+    #[should_panic(expected = "modulus must not be zero")]
+    fn factorial_mod_zero() {
+        crate::factorial::factorial_mod(5, 0);
+    }
+
+    #[test]
+    fn factorial_const() {
+        use crate::factorial::{checked_factorial, factorial_const};
+
+        const F5: u64 = factorial_const(5);
+        const TABLE: [u64; 4] = [factorial_const(0), factorial_const(1), factorial_const(10), factorial_const(20)];
+        assert_eq!(120, F5);
+        assert_eq!([1, 1, 3_628_800, 2_432_902_008_176_640_000], TABLE);
+
+        // And it's still a normal function at runtime
+        for n in 0..=20 {
+            assert_eq!(checked_factorial(n), Some(factorial_const(n)));
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "overflows")]
+    fn factorial_const_overflow() {
+        // const F21: u64 = factorial_const(21); // ERROR! Evaluating the constant panics
+        let n = std::hint::black_box(21);
+        crate::factorial::factorial_const(n);
+    }
+
+    #[test]
+    fn factorial_seq() {
+        use crate::factorial::{checked_factorial, FactorialSeq};
+
+        let table: Vec<u64> = FactorialSeq::new().collect();
+        // 0! to 20!, 21! doesn't fit
+        assert_eq!(21, table.len());
+        assert_eq!(vec![1, 1, 2, 6, 24, 120], table[..6]);
+        assert_eq!(2_432_902_008_176_640_000, table[20]);
+        for (n, value) in table.iter().enumerate() {
+            assert_eq!(checked_factorial(n as u32), Some(*value));
+        }
+
+        let mut seq = FactorialSeq::new().skip(20);
+        assert_eq!(Some(2_432_902_008_176_640_000), seq.next());
+        assert_eq!(None, seq.next());
+        assert_eq!(None, seq.next());
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn factorial_digit_sum() {
+        use crate::factorial::factorial_digit_sum;
+
+        // 10! = 3_628_800
+        assert_eq!(27, factorial_digit_sum(10));
+        assert_eq!(1, factorial_digit_sum(0));
+        assert_eq!(648, factorial_digit_sum(100));
+        // Still exact right past what a `u64` holds: 21! = 51_090_942_171_709_440_000
+        assert_eq!(63, factorial_digit_sum(21));
+    }
+
+    #[cfg(feature = "parallel")]
+    #[test]
+    fn factorials_parallel_matches_sequential() {
+        use crate::factorial::{checked_factorial, factorials_parallel};
+
+        let inputs: Vec<u32> = vec![0, 1, 5, 20, 21, 10, 100, 3];
+        let sequential: Vec<Option<u64>> = inputs.iter().map(|n| checked_factorial(*n)).collect();
+
+        assert_eq!(sequential, factorials_parallel(&inputs));
+        assert_eq!(Some(2_432_902_008_176_640_000), factorials_parallel(&inputs)[3]);
+        assert_eq!(None, factorials_parallel(&inputs)[4]);
+    }
 }
 
@@ -0,0 +1,51 @@
+use unicode_segmentation::UnicodeSegmentation;
+
+// A `char` is a unicode scalar value, not what a person sees as a character. An `é` can be an `e`
+// followed by a combining accent, and a flag is two "regional indicator" chars. What users see
+// are grapheme clusters, and `unicode-segmentation` knows how to split them.
+//
+// Notice this is an extension trait built on top of another crate's extension trait!
+#[allow(unused)]
+pub trait Graphemes {
+    fn grapheme_count(&self) -> usize;
+    fn graphemes_vec(&self) -> Vec<String>;
+}
+
+impl Graphemes for str {
+    fn grapheme_count(&self) -> usize {
+        self.graphemes(true).count()
+    }
+
+    fn graphemes_vec(&self) -> Vec<String> {
+        self.graphemes(true).map(String::from).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::graphemes::Graphemes;
+
+    #[test]
+    fn combining_characters() {
+        // "e" + COMBINING ACUTE ACCENT
+        let cafe = "cafe\u{301}";
+        assert_eq!(5, cafe.chars().count());
+        assert_eq!(4, cafe.grapheme_count());
+        assert_eq!(vec!["c", "a", "f", "e\u{301}"], cafe.graphemes_vec());
+    }
+
+    #[test]
+    fn emoji_sequences() {
+        // The Spanish flag is two regional indicators
+        let flag = "🇪🇸";
+        assert_eq!(2, flag.chars().count());
+        assert_eq!(1, flag.grapheme_count());
+
+        // Family: man, ZWJ, woman, ZWJ, girl
+        let family = "👨\u{200d}👩\u{200d}👧";
+        assert_eq!(1, family.grapheme_count());
+
+        assert_eq!(6, "¡Hola!".grapheme_count());
+        assert_eq!(0, "".grapheme_count());
+    }
+}
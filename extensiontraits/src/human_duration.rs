@@ -0,0 +1,56 @@
+use std::time::Duration;
+
+// `Duration` is a foreign type, and its `Debug` prints `3723s` or `1.5s`. Nothing stops us from
+// giving it a friendlier format: "1h 2m 3s".
+#[allow(unused)]
+pub trait HumanDuration {
+    fn human_readable(&self) -> String;
+}
+
+impl HumanDuration for Duration {
+    // Hours, minutes and seconds, leaving out the ones that are zero. The fraction of a second is
+    // dropped, unless there is nothing but the fraction: then it's "500ms", or "µs" and "ns" when smaller.
+    fn human_readable(&self) -> String {
+        let secs = self.as_secs();
+        if secs == 0 {
+            return match (self.as_millis(), self.as_micros(), self.as_nanos()) {
+                (_, _, 0) => String::from("0s"),
+                (0, 0, nanos) => format!("{nanos}ns"),
+                (0, micros, _) => format!("{micros}µs"),
+                (millis, _, _) => format!("{millis}ms"),
+            };
+        }
+
+        [(secs / 3600, "h"), (secs / 60 % 60, "m"), (secs % 60, "s")]
+            .iter()
+            .filter(|(amount, _)| *amount > 0)
+            .map(|(amount, unit)| format!("{amount}{unit}"))
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use crate::human_duration::HumanDuration;
+
+    #[test]
+    fn components() {
+        assert_eq!("1h 2m 3s", Duration::from_secs(3723).human_readable());
+        assert_eq!("1h 3s", Duration::from_secs(3603).human_readable());
+        assert_eq!("2m", Duration::from_secs(120).human_readable());
+        assert_eq!("25h", Duration::from_secs(25 * 3600).human_readable());
+        // The fraction doesn't count once there are whole seconds
+        assert_eq!("1s", Duration::from_millis(1500).human_readable());
+    }
+
+    #[test]
+    fn sub_second_and_zero() {
+        assert_eq!("500ms", Duration::from_millis(500).human_readable());
+        assert_eq!("250µs", Duration::from_micros(250).human_readable());
+        assert_eq!("7ns", Duration::from_nanos(7).human_readable());
+        assert_eq!("0s", Duration::ZERO.human_readable());
+    }
+}
@@ -0,0 +1,51 @@
+// Another itertools-style adaptor: takes one item from `self`, one from `other`, and so on.
+// When one side runs out, the rest of the other one follows.
+#[allow(unused)]
+pub trait Interleave: Iterator + Sized {
+    fn interleave<J>(self, other: J) -> impl Iterator<Item = Self::Item>
+    where
+        J: IntoIterator<Item = Self::Item>;
+}
+
+impl<I: Iterator> Interleave for I {
+    fn interleave<J>(self, other: J) -> impl Iterator<Item = Self::Item>
+    where
+        J: IntoIterator<Item = Self::Item>,
+    {
+        // `fuse` guarantees a finished iterator keeps returning `None`, not every iterator does.
+        let mut a = self.fuse();
+        let mut b = other.into_iter().fuse();
+        let mut a_turn = true;
+
+        std::iter::from_fn(move || {
+            let item = if a_turn { a.next().or_else(|| b.next()) } else { b.next().or_else(|| a.next()) };
+            a_turn = !a_turn;
+            item
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::interleave::Interleave;
+
+    #[test]
+    fn alternates_and_keeps_the_rest() {
+        let merged: Vec<i32> = [1, 3, 5].into_iter().interleave([2, 4]).collect();
+        assert_eq!(vec![1, 2, 3, 4, 5], merged);
+
+        let merged: Vec<i32> = [1].into_iter().interleave([2, 4, 6, 8]).collect();
+        assert_eq!(vec![1, 2, 4, 6, 8], merged);
+
+        let words: Vec<&str> = "a b c".split(' ').interleave(["-", "-"]).collect();
+        assert_eq!("a-b-c", words.concat());
+    }
+
+    #[test]
+    fn empty_sides() {
+        let merged: Vec<i32> = std::iter::empty().interleave([1, 2]).collect();
+        assert_eq!(vec![1, 2], merged);
+        let merged: Vec<i32> = [1, 2].into_iter().interleave([]).collect();
+        assert_eq!(vec![1, 2], merged);
+    }
+}
@@ -0,0 +1,14 @@
+// `Factorial` doesn't need anything from the standard library, so it's also a library that works
+// in `#![no_std]` crates. The `std` feature is on by default and its `extern crate std` puts `std`
+// back in scope everywhere, so a plain `cargo build` doesn't notice `std` sneaking into the
+// core-only part. The only real check is `cargo build --lib --no-default-features` (and
+// `cargo test --doc --no-default-features` for the doc tests).
+#![no_std]
+
+// `Urgency` works on `String`, which only needs an allocator, not the whole standard library.
+extern crate alloc;
+#[cfg(any(feature = "std", test))]
+extern crate std;
+
+pub mod factorial;
+pub mod urgency;
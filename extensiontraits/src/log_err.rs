@@ -0,0 +1,45 @@
+use std::fmt::Display;
+
+// Extension traits work for generic types from the standard library too. `Result` has plenty of
+// methods already, but not this one: report the error and move on.
+//
+// Instead of
+//     if let Err(err) = do_something() { eprintln!("{}", err); }
+// we can write
+//     do_something().log_err();
+#[allow(unused)]
+pub trait LogErr<T, E> {
+    fn log_err(self) -> Option<T>;
+}
+
+impl<T, E: Display> LogErr<T, E> for Result<T, E> {
+    fn log_err(self) -> Option<T> {
+        match self {
+            Ok(value) => Some(value),
+            Err(err) => {
+                eprintln!("{}", err);
+                None
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::log_err::LogErr;
+
+    #[test]
+    fn ok_is_kept() {
+        let result: Result<i32, String> = Ok(5);
+        assert_eq!(Some(5), result.log_err());
+    }
+
+    #[test]
+    fn err_is_logged_and_dropped() {
+        let result: Result<i32, String> = Err(String::from("something went wrong"));
+        assert_eq!(None, result.log_err());
+
+        let parsed = "not a number".parse::<i32>().log_err();
+        assert_eq!(None, parsed);
+    }
+}
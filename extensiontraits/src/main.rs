@@ -1,11 +1,29 @@
-mod urgency;
-mod factorial;
 mod more_factorial;
+mod clamp;
+mod sorted_insert;
+mod chunked;
+mod word_count;
+mod log_err;
+mod graphemes;
+mod pairs;
+mod shout;
+mod capitalize;
+mod pipe;
+mod reverse_words;
+mod average;
+mod parse_kv;
+mod palindrome;
+mod char_freq;
+mod interleave;
+mod slugify;
+mod zip_with;
+mod human_duration;
+mod transformer;
 
 // Once we import the trait, it is available
-use crate::urgency::Urgency;
+use extensiontraits::urgency::Urgency;
 
-use crate::factorial::Factorial;
+use extensiontraits::factorial::Factorial;
 
 // Uncomment to see the world on fire
 // use crate::more_factorial::DifferentFactorial;
@@ -0,0 +1,41 @@
+// Implementing for `[T]`, the slice, means arrays and `Vec`s get the method too.
+#[allow(unused)]
+pub trait Pairs<T> {
+    fn adjacent_pairs<'a>(&'a self) -> impl Iterator<Item = (&'a T, &'a T)>
+    where
+        T: 'a;
+}
+
+impl<T> Pairs<T> for [T] {
+    // `windows(2)` already walks every consecutive pair, we only make it a friendlier tuple.
+    // Less than two elements means no window at all.
+    fn adjacent_pairs<'a>(&'a self) -> impl Iterator<Item = (&'a T, &'a T)>
+    where
+        T: 'a,
+    {
+        self.windows(2).map(|pair| (&pair[0], &pair[1]))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::pairs::Pairs;
+
+    #[test]
+    fn consecutive_pairs() {
+        let pairs: Vec<(&i32, &i32)> = [1, 2, 3].adjacent_pairs().collect();
+        assert_eq!(vec![(&1, &2), (&2, &3)], pairs);
+
+        // Handy for diffing: the step between each value
+        let squares: Vec<i32> = (1..=4).map(|n| n * n).collect();
+        let steps: Vec<i32> = squares.adjacent_pairs().map(|(a, b)| b - a).collect();
+        assert_eq!(vec![3, 5, 7], steps);
+    }
+
+    #[test]
+    fn short_slices_have_no_pairs() {
+        assert_eq!(0, [1].adjacent_pairs().count());
+        let empty: [i32; 0] = [];
+        assert_eq!(0, empty.adjacent_pairs().count());
+    }
+}
@@ -0,0 +1,43 @@
+// Punctuation, spaces and case don't count: only the letters and digits have to read the same
+// both ways.
+#[allow(unused)]
+pub trait Palindrome {
+    fn is_palindrome(&self) -> bool;
+}
+
+impl Palindrome for str {
+    // No intermediate `String`: `chars` is double ended, so we walk it from both sides at once
+    // and compare. `flat_map(to_lowercase)` because lowercasing a `char` can give more than one.
+    fn is_palindrome(&self) -> bool {
+        let normalized = || self.chars().filter(|c| c.is_alphanumeric()).flat_map(char::to_lowercase);
+        normalized().eq(normalized().rev())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::palindrome::Palindrome;
+
+    #[test]
+    fn palindromes() {
+        assert!("A man, a plan, a canal: Panama".is_palindrome());
+        assert!("racecar".is_palindrome());
+        assert!("Anita lava la tina".is_palindrome());
+        assert!("12321".is_palindrome());
+    }
+
+    #[test]
+    fn not_palindromes() {
+        assert!(!"hello".is_palindrome());
+        assert!(!"ab".is_palindrome());
+        // Accents do count, `á` is not `a`
+        assert!(!"Dábale arroz a la zorra el abad".is_palindrome());
+    }
+
+    #[test]
+    fn trivial_cases() {
+        assert!("".is_palindrome());
+        assert!("!?".is_palindrome());
+        assert!("a".is_palindrome());
+    }
+}
@@ -0,0 +1,50 @@
+use std::collections::HashMap;
+
+// Config strings, query strings, cookies... all of them are `key=value` pairs glued together with
+// some separator. Both separators are parameters so one trait reads `a=1;b=2` and `a:1,b:2`.
+#[allow(unused)]
+pub trait ParseKv {
+    fn parse_kv(&self, pair_sep: char, kv_sep: char) -> HashMap<String, String>;
+}
+
+impl ParseKv for str {
+    // A pair without `kv_sep` is skipped, but an empty value (`a=`) is still a value.
+    // Only the first `kv_sep` splits, so values can contain it: `url=a=b` is `url` -> `a=b`.
+    // When a key repeats, the last one wins.
+    fn parse_kv(&self, pair_sep: char, kv_sep: char) -> HashMap<String, String> {
+        self.split(pair_sep)
+            .filter_map(|pair| pair.split_once(kv_sep))
+            .map(|(key, value)| (key.trim().to_string(), value.trim().to_string()))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use crate::parse_kv::ParseKv;
+
+    #[test]
+    fn well_formed() {
+        let map = "a=1;b=2".parse_kv(';', '=');
+        assert_eq!(HashMap::from([("a".into(), "1".into()), ("b".into(), "2".into())]), map);
+
+        let map = "name: Hector, age: 28".parse_kv(',', ':');
+        assert_eq!(Some(&String::from("Hector")), map.get("name"));
+        assert_eq!(Some(&String::from("28")), map.get("age"));
+    }
+
+    #[test]
+    fn empty_value_and_malformed_pairs() {
+        let map = "a=;b=2".parse_kv(';', '=');
+        assert_eq!(Some(&String::new()), map.get("a"));
+
+        let map = "a=1;oops;b=2;".parse_kv(';', '=');
+        assert_eq!(2, map.len());
+        assert_eq!(None, map.get("oops"));
+
+        assert_eq!(Some(&String::from("a=b")), "url=a=b".parse_kv(';', '=').get("url"));
+        assert!("".parse_kv(';', '=').is_empty());
+    }
+}
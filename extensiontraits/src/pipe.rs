@@ -0,0 +1,75 @@
+use extensiontraits::urgency::Urgency;
+
+use crate::capitalize::Capitalize;
+
+// Extension traits are nice, but the `Urgency` ones mutate in place and can't be chained.
+// `StrPipe` wraps them in a fluent API: every method only records a step, and nothing runs until
+// `collect`. Each step is a boxed closure because every closure has its own type.
+#[allow(dead_code)]
+pub struct StrPipe {
+    input: String,
+    steps: Vec<Box<dyn Fn(String) -> String>>,
+}
+
+#[allow(dead_code)]
+impl StrPipe {
+    pub fn new(input: impl Into<String>) -> Self {
+        Self { input: input.into(), steps: Vec::new() }
+    }
+
+    fn step(mut self, step: impl Fn(String) -> String + 'static) -> Self {
+        self.steps.push(Box::new(step));
+        self
+    }
+
+    pub fn urgency(self) -> Self {
+        self.step(|mut s| {
+            s.add_urgency();
+            s
+        })
+    }
+
+    pub fn spanish_urgency(self) -> Self {
+        self.step(|mut s| {
+            s.add_urgency_in_spanish();
+            s
+        })
+    }
+
+    pub fn title_case(self) -> Self {
+        self.step(|s| title_case(&s))
+    }
+
+    // Counts characters, not bytes, so it never cuts one in half.
+    pub fn truncate(self, max_chars: usize) -> Self {
+        self.step(move |s| s.chars().take(max_chars).collect())
+    }
+
+    pub fn collect(self) -> String {
+        self.steps.iter().fold(self.input, |s, step| step(s))
+    }
+}
+
+// Every word goes through `capitalize_first`, so the whitespace between them is kept.
+pub fn title_case(s: &str) -> String {
+    s.split_inclusive(char::is_whitespace).map(str::capitalize_first).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::pipe::StrPipe;
+
+    #[test]
+    fn chains_in_order() {
+        assert_eq!("¡Hola Mundo!", StrPipe::new("hola mundo").title_case().spanish_urgency().collect());
+        assert_eq!("¡Hola Mun", StrPipe::new("hola mundo").title_case().spanish_urgency().truncate(9).collect());
+        assert_eq!("¡hola!", StrPipe::new("hola").spanish_urgency().title_case().truncate(10).collect());
+        assert_eq!("Hola!", StrPipe::new("hola mundo").truncate(4).urgency().title_case().collect());
+    }
+
+    #[test]
+    fn empty_pipe_is_identity() {
+        assert_eq!("hola  mundo", StrPipe::new("hola  mundo").collect());
+        assert_eq!("Hola  Mundo", StrPipe::new("hola  mundo").title_case().collect());
+    }
+}
@@ -0,0 +1,32 @@
+// Reverses the order of the words, not their letters: "hello world" is "world hello".
+// Going through `split_whitespace` also collapses any run of whitespace into a single space.
+#[allow(unused)]
+pub trait ReverseWords {
+    fn reverse_words(&self) -> String;
+}
+
+impl ReverseWords for str {
+    fn reverse_words(&self) -> String {
+        // `split_whitespace` is double ended, so it can be walked from the back
+        self.split_whitespace().rev().collect::<Vec<_>>().join(" ")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::reverse_words::ReverseWords;
+
+    #[test]
+    fn reverses_word_order() {
+        assert_eq!("foo world hello", "hello world foo".reverse_words());
+        assert_eq!("foo world hello", "  hello   world\tfoo ".reverse_words());
+        assert_eq!("mundo! ¡Hola", "¡Hola mundo!".reverse_words());
+    }
+
+    #[test]
+    fn single_word_and_empty() {
+        assert_eq!("hello", "hello".reverse_words());
+        assert_eq!("", "".reverse_words());
+        assert_eq!("", "   ".reverse_words());
+    }
+}
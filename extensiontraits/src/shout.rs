@@ -0,0 +1,56 @@
+use extensiontraits::urgency::Urgency;
+
+// How much is a string shouting? Half of it is the capital letters, the other half the trailing
+// exclamation marks (three or more is full shouting).
+#[allow(unused)]
+pub trait Shout {
+    fn shout_score(&self) -> f32;
+    fn to_shout(&self) -> String;
+}
+
+const FULL_SHOUT_MARKS: usize = 3;
+
+impl Shout for str {
+    fn shout_score(&self) -> f32 {
+        let letters = self.chars().filter(|c| c.is_alphabetic()).count();
+        let upper = self.chars().filter(|c| c.is_uppercase()).count();
+        let upper_ratio = if letters == 0 { 0.0 } else { upper as f32 / letters as f32 };
+
+        let marks = self.chars().rev().take_while(|c| *c == '!').count().min(FULL_SHOUT_MARKS);
+        let marks_ratio = marks as f32 / FULL_SHOUT_MARKS as f32;
+
+        (upper_ratio + marks_ratio) / 2.0
+    }
+
+    // Extension traits compose: the `Urgency` we wrote for `String` does the exclamation marks.
+    fn to_shout(&self) -> String {
+        let mut shout = self.to_uppercase();
+        for _ in 0..FULL_SHOUT_MARKS {
+            shout.add_urgency();
+        }
+        shout
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::shout::Shout;
+
+    #[test]
+    fn shout_score() {
+        assert_eq!(0.0, "hello".shout_score());
+        assert_eq!(1.0, "HELLO!!!".shout_score());
+        assert_eq!(1.0, "HELLO!!!!!".shout_score());
+        assert_eq!(0.5, "HELLO".shout_score());
+        assert!("hello".shout_score() < "Hello!".shout_score());
+        assert!("Hello!".shout_score() < "HELLO!!!".shout_score());
+        assert_eq!(0.0, "".shout_score());
+    }
+
+    #[test]
+    fn to_shout() {
+        assert_eq!("HELLO!!!", "hello".to_shout());
+        assert_eq!("¡HOLA!!!!", "¡Hola!".to_shout());
+        assert_eq!(1.0, "hello".to_shout().shout_score());
+    }
+}
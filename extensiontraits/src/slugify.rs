@@ -0,0 +1,73 @@
+// Turns a title into something that fits in a URL: "¡Hola, Señor!" is "hola-senor".
+#[allow(unused)]
+pub trait Slugify {
+    fn slugify(&self) -> String;
+}
+
+// Only the common Latin accents, a full transliteration needs the Unicode tables of a crate
+// like `deunicode`. Anything else that is not ASCII is dropped.
+fn transliterate(c: char) -> Option<&'static str> {
+    Some(match c {
+        'á' | 'à' | 'â' | 'ä' | 'ã' | 'å' => "a",
+        'é' | 'è' | 'ê' | 'ë' => "e",
+        'í' | 'ì' | 'î' | 'ï' => "i",
+        'ó' | 'ò' | 'ô' | 'ö' | 'õ' => "o",
+        'ú' | 'ù' | 'û' | 'ü' => "u",
+        'ñ' => "n",
+        'ç' => "c",
+        'ß' => "ss",
+        _ => return None,
+    })
+}
+
+impl Slugify for str {
+    // Whitespace and punctuation only mark that a hyphen is due. It's written once the next
+    // letter arrives, so runs collapse and there is never one at either end.
+    fn slugify(&self) -> String {
+        let mut slug = String::with_capacity(self.len());
+        let mut hyphen = false;
+
+        for c in self.chars().flat_map(char::to_lowercase) {
+            let piece = match c {
+                c if c.is_ascii_alphanumeric() => String::from(c),
+                c if c.is_whitespace() || c.is_ascii_punctuation() || c == '¡' || c == '¿' => {
+                    hyphen = true;
+                    continue;
+                },
+                c => match transliterate(c) {
+                    Some(ascii) => ascii.to_string(),
+                    None => continue,
+                },
+            };
+
+            if hyphen && !slug.is_empty() {
+                slug.push('-');
+            }
+            hyphen = false;
+            slug.push_str(&piece);
+        }
+
+        slug
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::slugify::Slugify;
+
+    #[test]
+    fn punctuation_and_spaces() {
+        assert_eq!("hello-world", "Hello, World!".slugify());
+        assert_eq!("multiple-spaces", "  Multiple   Spaces  ".slugify());
+        assert_eq!("a-b-c", "a--b__c".slugify());
+        assert_eq!("", "  !!  ".slugify());
+    }
+
+    #[test]
+    fn accents_and_other_characters() {
+        assert_eq!("hola-senor", "¡Hola, Señor!".slugify());
+        assert_eq!("que-tal", "¿Qué tal?".slugify());
+        assert_eq!("strasse", "Straße".slugify());
+        assert_eq!("rust-2024", "Rust 🦀 2024".slugify());
+    }
+}
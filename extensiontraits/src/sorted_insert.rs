@@ -0,0 +1,45 @@
+// Extension traits can be generic too. This one works for a `Vec` of anything that can be ordered.
+#[allow(unused)]
+pub trait SortedInsert<T: Ord> {
+    fn insert_sorted(&mut self, item: T) -> usize;
+}
+
+impl<T: Ord> SortedInsert<T> for Vec<T> {
+    // The vec must already be sorted. `partition_point` is a binary search for the first element
+    // that is bigger than `item`, so equal elements stay before the new one.
+    fn insert_sorted(&mut self, item: T) -> usize {
+        let index = self.partition_point(|x| *x <= item);
+        self.insert(index, item);
+        index
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::sorted_insert::SortedInsert;
+
+    #[test]
+    fn insert_into_empty() {
+        let mut v: Vec<i32> = Vec::new();
+        assert_eq!(0, v.insert_sorted(5));
+        assert_eq!(vec![5], v);
+    }
+
+    #[test]
+    fn insert_front_and_middle() {
+        let mut v = vec![2, 4, 6];
+        assert_eq!(0, v.insert_sorted(1));
+        assert_eq!(vec![1, 2, 4, 6], v);
+        assert_eq!(3, v.insert_sorted(5));
+        assert_eq!(vec![1, 2, 4, 5, 6], v);
+        assert_eq!(5, v.insert_sorted(7));
+        assert_eq!(vec![1, 2, 4, 5, 6, 7], v);
+    }
+
+    #[test]
+    fn insert_duplicate_goes_after_equals() {
+        let mut v = vec![1, 2, 2, 3];
+        assert_eq!(3, v.insert_sorted(2));
+        assert_eq!(vec![1, 2, 2, 2, 3], v);
+    }
+}
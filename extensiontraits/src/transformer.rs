@@ -0,0 +1,96 @@
+use extensiontraits::urgency::Urgency;
+
+use crate::pipe;
+use crate::slugify::Slugify;
+
+// `StrPipe` is built, run once on its input and gone. A `TextTransformer` holds the steps on
+// their own, so the same pipeline can be applied to as many strings as needed. Every step edits
+// the string in place, the same shape as the `Urgency` methods.
+pub type Transform = Box<dyn Fn(&mut String)>;
+
+#[allow(dead_code)]
+#[derive(Default)]
+pub struct TextTransformer {
+    steps: Vec<Transform>,
+}
+
+#[allow(dead_code)]
+impl TextTransformer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn add(mut self, transform: impl Fn(&mut String) + 'static) -> Self {
+        self.steps.push(Box::new(transform));
+        self
+    }
+
+    // In the order they were added.
+    pub fn apply(&self, s: &mut String) {
+        for step in &self.steps {
+            step(s);
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.steps.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.steps.is_empty()
+    }
+}
+
+// The existing transformations as steps, ready for `add`: `TextTransformer::new().add(urgency)`.
+#[allow(dead_code)]
+pub fn urgency(s: &mut String) {
+    s.add_urgency();
+}
+
+#[allow(dead_code)]
+pub fn spanish_urgency(s: &mut String) {
+    s.add_urgency_in_spanish();
+}
+
+#[allow(dead_code)]
+pub fn title_case(s: &mut String) {
+    *s = pipe::title_case(s);
+}
+
+#[allow(dead_code)]
+pub fn slugify(s: &mut String) {
+    *s = s.slugify();
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::transformer::{slugify, spanish_urgency, title_case, urgency, TextTransformer};
+
+    #[test]
+    fn applies_steps_in_order() {
+        let shout = TextTransformer::new().add(title_case).add(spanish_urgency);
+        assert_eq!(2, shout.len());
+
+        let mut hello = String::from("hola mundo");
+        shout.apply(&mut hello);
+        assert_eq!("¡Hola Mundo!", hello);
+
+        // Reusable: same pipeline, another string
+        let mut bye = String::from("adios");
+        shout.apply(&mut bye);
+        assert_eq!("¡Adios!", bye);
+    }
+
+    #[test]
+    fn custom_steps_and_empty() {
+        let slug = TextTransformer::new().add(urgency).add(slugify).add(|s: &mut String| s.push_str(".html"));
+        let mut title = String::from("Hello World");
+        slug.apply(&mut title);
+        assert_eq!("hello-world.html", title);
+
+        let mut untouched = String::from("same");
+        TextTransformer::new().apply(&mut untouched);
+        assert_eq!("same", untouched);
+        assert!(TextTransformer::new().is_empty());
+    }
+}
@@ -1,8 +1,16 @@
+use alloc::string::String;
+
 // Lets first create the trait.
 #[allow(unused)]
 pub trait Urgency {
     fn add_urgency(&mut self);
     fn add_urgency_in_spanish(&mut self);
+    fn add_urgency_trimmed(&mut self);
+    fn add_urgency_both(&mut self);
+    fn normalize_urgency(&mut self);
+    // Not every method has to mutate. These ones just read the string.
+    fn is_urgent(&self) -> bool;
+    fn urgency_level(&self) -> usize;
 }
 
 
@@ -17,6 +25,67 @@ impl Urgency for String {
         self.insert(0, '¡');
         self.push('!');
     }
+
+    // Spanish first, then English: "Hola" -> "¡Hola!" -> "¡Hola!!". The other way around gives
+    // the same string, but this order reads like what it is, a Spanish phrase with extra urgency.
+    fn add_urgency_both(&mut self) {
+        self.add_urgency_in_spanish();
+        self.add_urgency();
+    }
+
+    // "Hi!!!" becomes "Hi!": however urgent, one mark is enough. Without marks nothing changes.
+    fn normalize_urgency(&mut self) {
+        let level = self.urgency_level();
+        if level > 1 {
+            // `!` is one byte, so the length in bytes is the length in chars
+            self.truncate(self.len() - (level - 1));
+        }
+    }
+
+    // "Hello " becomes "Hello! " instead of "Hello !".
+    fn add_urgency_trimmed(&mut self) {
+        let end = self.trim_end().len();
+        self.insert(end, '!');
+    }
+
+    fn is_urgent(&self) -> bool {
+        self.urgency_level() > 0
+    }
+
+    fn urgency_level(&self) -> usize {
+        self.chars().rev().take_while(|c| *c == '!').count()
+    }
+}
+
+// Extension traits play well with the rest of the standard library traits too.
+// Here is an iterator that yields every urgency level of a string, from calm to panic.
+#[allow(dead_code)]
+pub struct UrgencyIter {
+    current: String,
+    level: usize,
+    max: usize,
+}
+
+#[allow(dead_code)]
+impl UrgencyIter {
+    pub fn new(base: impl Into<String>, max: usize) -> Self {
+        Self { current: base.into(), level: 0, max }
+    }
+}
+
+impl Iterator for UrgencyIter {
+    type Item = String;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.level > self.max {
+            return None;
+        }
+        let variation = self.current.clone();
+        // Our own extension trait, used to drive the iterator.
+        self.current.add_urgency();
+        self.level += 1;
+        Some(variation)
+    }
 }
 
 #[allow(dead_code)]
@@ -36,7 +105,11 @@ fn example() {
 
 #[cfg(test)]
 mod tests {
-    use crate::urgency::Urgency;
+    use std::string::{String, ToString};
+    use std::vec;
+    use std::vec::Vec;
+
+    use crate::urgency::{Urgency, UrgencyIter};
 
     #[test]
     fn should_append_urgency() {
@@ -68,4 +141,65 @@ mod tests {
             assert_eq!("¡Hola, que tal!".to_string(), hello);
         }
     }
+
+    #[test]
+    fn urgency_in_both_languages() {
+        let mut hello = "Hola".to_string();
+        hello.add_urgency_both();
+        assert_eq!("¡Hola!!", hello);
+        assert_eq!(2, hello.urgency_level());
+    }
+
+    #[test]
+    fn normalize_urgency() {
+        for (input, expected) in [("Hi!!!", "Hi!"), ("Hi", "Hi"), ("Hi!", "Hi!"), ("!!", "!"), ("¡Hola!!", "¡Hola!"), ("", "")] {
+            let mut s = input.to_string();
+            s.normalize_urgency();
+            assert_eq!(expected, s);
+        }
+    }
+
+    #[test]
+    fn urgency_variations() {
+        let variations: Vec<String> = UrgencyIter::new("Hi", 10).take(4).collect();
+        assert_eq!(vec!["Hi", "Hi!", "Hi!!", "Hi!!!"], variations);
+
+        // Up to and including `max` exclamation marks
+        assert_eq!(3, UrgencyIter::new("Hi", 2).count());
+    }
+
+    #[test]
+    fn urgency_detection() {
+        let hello = String::from("Hello!!!");
+        assert_eq!(3, hello.urgency_level());
+        assert!(hello.is_urgent());
+
+        let calm = String::from("Hello");
+        assert_eq!(0, calm.urgency_level());
+        assert!(!calm.is_urgent());
+
+        // Only the trailing ones count
+        let middle = String::from("Hello! world");
+        assert_eq!(0, middle.urgency_level());
+        assert!(!middle.is_urgent());
+    }
+
+    #[test]
+    fn urgency_before_trailing_whitespace() {
+        {
+            let mut hello = String::from("Hello ");
+            hello.add_urgency_trimmed();
+            assert_eq!("Hello! ".to_string(), hello);
+        }
+        {
+            let mut hello = String::from("Hello \n\t");
+            hello.add_urgency_trimmed();
+            assert_eq!("Hello! \n\t".to_string(), hello);
+        }
+        {
+            let mut hello = String::from("Hello");
+            hello.add_urgency_trimmed();
+            assert_eq!("Hello!".to_string(), hello);
+        }
+    }
 }
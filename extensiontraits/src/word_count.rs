@@ -0,0 +1,41 @@
+// Implementing the trait for `str` instead of `String` means both get it:
+// a `String` derefs to a `str`, so `String::from("a b").word_count()` works too.
+#[allow(unused)]
+pub trait WordCount {
+    fn word_count(&self) -> usize;
+    fn char_count_no_spaces(&self) -> usize;
+}
+
+impl WordCount for str {
+    // `split_whitespace` already ignores leading, trailing and repeated whitespace.
+    fn word_count(&self) -> usize {
+        self.split_whitespace().count()
+    }
+
+    fn char_count_no_spaces(&self) -> usize {
+        self.chars().filter(|c| !c.is_whitespace()).count()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::word_count::WordCount;
+
+    #[test]
+    fn counts_words() {
+        assert_eq!(2, "hello world".word_count());
+        assert_eq!(3, "  a  b  c ".word_count());
+        assert_eq!(0, "".word_count());
+        assert_eq!(0, "   ".word_count());
+        assert_eq!(2, String::from("hola amigos").word_count());
+    }
+
+    #[test]
+    fn counts_chars_without_spaces() {
+        assert_eq!(10, "hello world".char_count_no_spaces());
+        assert_eq!(3, "  a  b  c ".char_count_no_spaces());
+        assert_eq!(0, "".char_count_no_spaces());
+        // Characters, not bytes
+        assert_eq!(6, "¡Hola!".char_count_no_spaces());
+    }
+}
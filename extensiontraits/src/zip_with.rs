@@ -0,0 +1,41 @@
+// `a.zip(b).map(|(x, y)| f(x, y))` without the tuple in the middle, like Haskell's `zipWith`.
+// It stops as soon as either side runs out, exactly like `zip`.
+#[allow(unused)]
+pub trait ZipWith: Iterator + Sized {
+    fn zip_with<U, F, R>(self, other: U, f: F) -> impl Iterator<Item = R>
+    where
+        U: IntoIterator,
+        F: FnMut(Self::Item, U::Item) -> R;
+}
+
+impl<I: Iterator> ZipWith for I {
+    fn zip_with<U, F, R>(mut self, other: U, mut f: F) -> impl Iterator<Item = R>
+    where
+        U: IntoIterator,
+        F: FnMut(Self::Item, U::Item) -> R,
+    {
+        let mut other = other.into_iter();
+        // `self` goes first: if it's empty, `other` is never touched
+        std::iter::from_fn(move || Some(f(self.next()?, other.next()?)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::zip_with::ZipWith;
+
+    #[test]
+    fn element_wise() {
+        let sums: Vec<i32> = [1, 2, 3].into_iter().zip_with([10, 20, 30], |a, b| a + b).collect();
+        assert_eq!(vec![11, 22, 33], sums);
+
+        let labels: Vec<String> = ["a", "b"].into_iter().zip_with(1.., |s, n| format!("{s}{n}")).collect();
+        assert_eq!(vec!["a1", "b2"], labels);
+    }
+
+    #[test]
+    fn stops_at_the_shorter() {
+        assert_eq!(2, [1, 2, 3].into_iter().zip_with([1, 1], |a, b| a * b).count());
+        assert_eq!(0, std::iter::empty::<i32>().zip_with([1, 2], |a, b| a - b).count());
+    }
+}
@@ -1,9 +1,31 @@
+mod pool;
+mod request;
+mod retryable;
+mod safe_file;
+mod traffic_light;
+mod transaction;
+mod wizard;
+
 use std::fmt::Display;
 use std::{fmt};
 use std::marker::PhantomData;
 
+/// The brightest a light can be.
+const MAX_INTENSITY: u8 = 100;
+
+/// Why a change on a `Light` was refused.
+#[derive(Debug, PartialEq)]
+#[allow(dead_code)]
+enum LightError {
+    /// The light is off, there is no intensity to regulate.
+    Off,
+    /// The requested intensity is above `MAX_INTENSITY`.
+    TooIntense(u8),
+}
+
 /// Let's imagine a Light struct. We can toggle it: light on means status is true, otherwise false.
 /// This would be the normal way to do it.
+#[derive(Debug, PartialEq)]
 struct Light {
     status:  bool,
     intensity: u8, // let's add a new attribute: intensity of the light.
@@ -17,7 +39,8 @@ impl Light {
         }
     }
     /// We just change the `status` attribute of Light.
-    fn toggle(&mut self) {
+    /// It returns `&mut Self` so calls can be chained: `light.toggle().regulate_intensity(50)`.
+    fn toggle(&mut self) -> &mut Self {
         // But now, does it make sense to have a turned off ligth with 100 intensity?
         // We should change the intensity to 0. But also, if we turned it on we should have an 
         // intensity of at least 1.
@@ -34,22 +57,119 @@ impl Light {
         }
 
         self.status = !self.status;
+        self
     }
 
 
+    /// Only the parity of `n` decides if the light ends on or off. The intensity is whatever the
+    /// last toggle would leave: 10 when it ends on, 0 when it ends off.
+    #[allow(dead_code)]
+    fn toggle_n(&mut self, n: usize) {
+        if n == 0 {
+            return;
+        }
+        if n % 2 == 1 {
+            self.status = !self.status;
+        }
+        self.intensity = if self.is_on() { 10 } else { 0 };
+    }
+
     /// But now, we want to change the intensity
-    fn regulate_intensity(&mut self, intensity: u8) {
+    fn regulate_intensity(&mut self, intensity: u8) -> &mut Self {
         // But we should check the status and only change it there right?
         // This is starting to snowball pretty fast.
         if self.is_on() {
             self.intensity = intensity;
         }
+        self
+    }
+
+    /// At least now we can tell the caller that something was wrong instead of ignoring it.
+    /// But notice it's the caller who has to deal with it, at runtime.
+    #[allow(dead_code)]
+    fn try_regulate_intensity(&mut self, intensity: u8) -> Result<(), LightError> {
+        if !self.is_on() {
+            return Err(LightError::Off);
+        }
+        if intensity > MAX_INTENSITY {
+            return Err(LightError::TooIntense(intensity));
+        }
+        self.intensity = intensity;
+        Ok(())
+    }
+
+    /// Moves the intensity to `target` in `steps` equal steps and returns every intensity it went
+    /// through, `target` included. With 0 steps it just jumps to `target` and returns nothing.
+    /// Fading an off light does nothing, again a runtime check.
+    #[allow(dead_code)]
+    fn fade_to(&mut self, target: u8, steps: usize) -> Vec<u8> {
+        if !self.is_on() {
+            return Vec::new();
+        }
+
+        // In i128 a `usize` step count fits without truncating, and `distance * step` can't
+        // overflow however many steps there are.
+        let start = i128::from(self.intensity);
+        let distance = i128::from(target) - start;
+        let total = steps as i128;
+        let fade: Vec<u8> = (1..=total)
+            .map(|step| (start + distance * step / total) as u8)
+            .collect();
+
+        self.intensity = target;
+        fade
+    }
+
+    /// A derived property: how warm the light looks, from 0.0 to 1.0. Like a dimmed bulb, the lower
+    /// the intensity the warmer, down to 0.1 at `MAX_INTENSITY` or above. An off light gives 0.0.
+    #[allow(dead_code)]
+    fn warmth(&self) -> f32 {
+        if !self.is_on() {
+            return 0.0;
+        }
+        let brightness = f32::from(self.intensity.min(MAX_INTENSITY)) / f32::from(MAX_INTENSITY);
+        1.0 - 0.9 * brightness
     }
 
     /// let's now make a function to know if the light is on or off
     fn is_on(&self) -> bool {
         self.status
     }
+
+    /// Two bytes per light, for storing a lot of them: the status is the top bit, the intensity
+    /// the low byte.
+    #[allow(dead_code)]
+    fn pack(&self) -> u16 {
+        (u16::from(self.status) << 15) | u16::from(self.intensity)
+    }
+
+    /// The inverse of `pack`. Nothing stops a `u16` from saying "off with intensity 50", so an
+    /// off light always comes back with intensity 0.
+    #[allow(dead_code)]
+    fn unpack(packed: u16) -> Light {
+        let status = packed & (1 << 15) != 0;
+        let intensity = if status { (packed & 0xFF) as u8 } else { 0 };
+        Light { status, intensity }
+    }
+
+    /// One byte for serial links: the status is bit 7 and the intensity, clamped to
+    /// `MAX_INTENSITY`, is scaled from 0-100 to the 0-127 that fits in the other seven bits.
+    #[allow(dead_code)]
+    fn to_byte(&self) -> u8 {
+        let intensity = u16::from(self.intensity.min(MAX_INTENSITY));
+        let scaled = (intensity * 127 + u16::from(MAX_INTENSITY) / 2) / u16::from(MAX_INTENSITY);
+        (u8::from(self.status) << 7) | scaled as u8
+    }
+
+    /// Scales the seven bits back to 0-100, rounding to the nearest value. Every intensity in
+    /// 0-100 comes back exactly, 127 steps are more than enough for 101 values.
+    #[allow(dead_code)]
+    fn from_byte(b: u8) -> Light {
+        let status = b & 0x80 != 0;
+        let scaled = u16::from(b & 0x7F);
+        let intensity = (scaled * u16::from(MAX_INTENSITY) + 63) / 127;
+        Light { status, intensity: if status { intensity as u8 } else { 0 } }
+    }
 }
 
 impl Display for Light {
@@ -84,7 +204,21 @@ fn light_one() {
 /// Instead of Light we are going to call it `Luz` which is Light but in Spanish.
 /// But, because we are integrating the status directly into the state machine we can do something
 /// about it.
-trait LuzState { }
+///
+/// Every state also carries its `KIND` as a constant. The type already knows which state it is,
+/// the constant only lets us ask at runtime without a method per state.
+trait LuzState {
+    const KIND: LuzStateKind;
+}
+
+/// What a `Luz<S>` is, as a plain value. Handy for logging or for a UI, where the type is gone.
+#[allow(dead_code)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum LuzStateKind {
+    Off,
+    On,
+    Dimmed,
+}
 
 #[derive(Debug)]
 struct LuzOff {}
@@ -92,8 +226,12 @@ struct LuzOff {}
 #[derive(Debug)]
 struct LuzOn {}
 
-impl LuzState for LuzOn { }
-impl LuzState for LuzOff { }
+#[derive(Debug)]
+struct LuzDimmed {}
+
+impl LuzState for LuzOn { const KIND: LuzStateKind = LuzStateKind::On; }
+impl LuzState for LuzOff { const KIND: LuzStateKind = LuzStateKind::Off; }
+impl LuzState for LuzDimmed { const KIND: LuzStateKind = LuzStateKind::Dimmed; }
 
 #[derive(Debug)]
 struct Luz<S: LuzState> {
@@ -102,7 +240,14 @@ struct Luz<S: LuzState> {
 }
 
 
+// Written once for every state: the answer comes from `S`, not from `self`.
+impl<S: LuzState> Luz<S> {
+    fn state(&self) -> LuzStateKind {
+        S::KIND
+    }
+}
 
+#[allow(dead_code)]
 impl Luz<LuzOn> {
     fn toggle(self) -> Luz<LuzOff> {
        Luz {
@@ -110,9 +255,16 @@ impl Luz<LuzOn> {
        }
     }
 
+    fn dim(self) -> Luz<LuzDimmed> {
+        Luz {
+            _marker: PhantomData
+        }
+    }
+
     fn is_on(&self) -> bool {true}
 }
 
+#[allow(dead_code)]
 impl Luz<LuzOff> {
     fn new() -> Luz<LuzOff> {
         Luz {
@@ -128,14 +280,76 @@ impl Luz<LuzOff> {
     fn is_on(&self) -> bool {false}
 }
 
+#[allow(dead_code)]
+impl Luz<LuzDimmed> {
+    fn toggle(self) -> Luz<LuzOff> {
+        Luz {
+            _marker: PhantomData
+        }
+    }
+
+    fn is_on(&self) -> bool {true}
+}
+
+/// How much power a `Luz` draws while it is on.
+const LUZ_WATTS: f64 = 10.0;
+
+/// The marker says which state we are in, the wrapper carries data that lives across all of them:
+/// the energy the light has used so far.
+#[derive(Debug)]
+#[allow(dead_code)]
+struct LuzMetered<S: LuzState> {
+    luz: Luz<S>,
+    watt_seconds: f64,
+}
+
+#[allow(dead_code)]
+impl<S: LuzState> LuzMetered<S> {
+    fn energy_used(&self) -> f64 {
+        self.watt_seconds
+    }
+}
+
+#[allow(dead_code)]
+impl LuzMetered<LuzOff> {
+    fn new() -> LuzMetered<LuzOff> {
+        LuzMetered { luz: Luz::new(), watt_seconds: 0.0 }
+    }
+
+    fn toggle(self) -> LuzMetered<LuzOn> {
+        LuzMetered { luz: self.luz.toggle(), watt_seconds: self.watt_seconds }
+    }
+
+    /// An off light uses nothing, time passing doesn't change the meter.
+    fn tick(&mut self, _secs: f64) {}
+}
+
+#[allow(dead_code)]
+impl LuzMetered<LuzOn> {
+    fn toggle(self) -> LuzMetered<LuzOff> {
+        LuzMetered { luz: self.luz.toggle(), watt_seconds: self.watt_seconds }
+    }
+
+    fn tick(&mut self, secs: f64) {
+        self.watt_seconds += LUZ_WATTS * secs;
+    }
+}
+
+#[allow(dead_code)]
 trait LumiereState {
     fn is_on(&self)  -> bool;
 }
 
 #[derive(Debug)]
+#[allow(dead_code)]
 struct LumiereOff();
 #[derive(Debug)]
+#[allow(dead_code)]
 struct LumiereOn(u8);
+/// Still on, but half as bright. It remembers the intensity it had so it can go back to it.
+#[derive(Debug)]
+#[allow(dead_code)]
+struct LumiereDimmed(u8);
 
 impl LumiereState for LumiereOn {
     fn is_on(&self)  -> bool { true }
@@ -143,17 +357,29 @@ impl LumiereState for LumiereOn {
 impl LumiereState for LumiereOff {
     fn is_on(&self)  -> bool { false }
 }
+impl LumiereState for LumiereDimmed {
+    fn is_on(&self)  -> bool { true }
+}
 
+#[allow(dead_code)]
 struct Lumiere<S: LumiereState> {
     state: S
 }
 
+#[allow(dead_code)]
 impl<S: LumiereState> Lumiere<S> {
     fn is_on(&self) -> bool {
         self.state.is_on()
     }
+
+    /// Peek at what `is_on` would report after a `toggle`, without consuming the light.
+    /// Every toggle flips the light, so it is always the opposite of the current state.
+    fn would_be_on(&self) -> bool {
+        !self.is_on()
+    }
 }
 
+#[allow(dead_code)]
 impl Lumiere<LumiereOff> {
     fn toggle(self) -> Lumiere<LumiereOn> {
         Lumiere {
@@ -168,6 +394,7 @@ impl Lumiere<LumiereOff> {
     }
 }
 
+#[allow(dead_code)]
 impl Lumiere<LumiereOn> {
     fn toggle(self) -> Lumiere<LumiereOff> {
         Lumiere {
@@ -182,8 +409,139 @@ impl Lumiere<LumiereOn> {
     fn get_intensity(&self) -> u8 {
         self.state.0 
     }
+
+    fn effective_intensity(&self) -> u8 {
+        self.state.0
+    }
+
+    /// A word for the UI. Only an on light has one, so there is no "Off" label to forget about.
+    /// Intensities above `MAX_INTENSITY` are still "Bright", and 0 is as dim as it gets.
+    fn intensity_label(&self) -> &'static str {
+        match self.state.0 {
+            0..=20 => "Dim",
+            21..=60 => "Medium",
+            _ => "Bright",
+        }
+    }
+
+    /// Like `Light::fade_to(0, steps)`, but there is no off light to fade at runtime: only an on
+    /// light has this method, and it hands back the intensities together with the off light.
+    /// With 0 steps it just turns off.
+    fn fade_off(self, steps: usize) -> (Vec<u8>, Lumiere<LumiereOff>) {
+        let start = self.state.0 as usize;
+        let fade = (1..=steps).map(|step| (start - start * step / steps) as u8).collect();
+        (fade, self.toggle())
+    }
+
+    /// The intensity travels with the transition, inside the state.
+    fn into_dimmed(self) -> Lumiere<LumiereDimmed> {
+        Lumiere {
+            state: LumiereDimmed(self.state.0)
+        }
+    }
+}
+
+#[allow(dead_code)]
+impl Lumiere<LumiereDimmed> {
+    fn toggle(self) -> Lumiere<LumiereOff> {
+        Lumiere {
+            state: LumiereOff()
+        }
+    }
+
+    fn into_on(self) -> Lumiere<LumiereOn> {
+        Lumiere {
+            state: LumiereOn(self.state.0)
+        }
+    }
+
+    fn get_intensity(&self) -> u8 {
+        self.state.0
+    }
+
+    /// What the light actually outputs: half of the stored intensity.
+    fn effective_intensity(&self) -> u8 {
+        self.state.0 / 2
+    }
+}
+
+/// The typed states are great when the code knows the state. When it comes from the outside (a
+/// saved preset, a user click) we need a single type that can hold any of them, checked at runtime.
+#[allow(dead_code)]
+enum AnyLumiere {
+    Off(Lumiere<LumiereOff>),
+    On(Lumiere<LumiereOn>),
+    Dimmed(Lumiere<LumiereDimmed>),
+}
+
+#[allow(dead_code)]
+impl AnyLumiere {
+    fn is_on(&self) -> bool {
+        match self {
+            AnyLumiere::Off(l) => l.is_on(),
+            AnyLumiere::On(l) => l.is_on(),
+            AnyLumiere::Dimmed(l) => l.is_on(),
+        }
+    }
+
+    /// `None` when the light is off: there is no intensity to speak of.
+    fn intensity(&self) -> Option<u8> {
+        match self {
+            AnyLumiere::Off(_) => None,
+            AnyLumiere::On(l) => Some(l.get_intensity()),
+            AnyLumiere::Dimmed(l) => Some(l.get_intensity()),
+        }
+    }
+}
+
+/// A named preset for a `Lumiere`. The name is owned, so scenes can also come from a user or a
+/// file, not only from the built-in ones below.
+#[derive(Debug, Clone, PartialEq)]
+#[allow(dead_code)]
+struct Scene {
+    name: String,
+    on: bool,
+    intensity: u8,
+}
+
+#[allow(dead_code)]
+impl Scene {
+    fn new(name: impl Into<String>, on: bool, intensity: u8) -> Self {
+        Scene { name: name.into(), on, intensity }
+    }
+
+    fn movie() -> Self { Scene::new("movie", true, 20) }
+
+    fn reading() -> Self { Scene::new("reading", true, 90) }
+}
+
+/// Every built-in scene, to look them up by name.
+#[allow(dead_code)]
+fn scenes() -> [Scene; 2] {
+    [Scene::movie(), Scene::reading()]
+}
+
+#[allow(dead_code)]
+fn find_scene(name: &str) -> Option<Scene> {
+    scenes().into_iter().find(|scene| scene.name == name)
 }
 
+/// Whatever state the light is in, it ends in the one the scene describes. Every step is still a
+/// typed transition, the enum only appears at the edges.
+#[allow(dead_code)]
+fn apply_scene(light: AnyLumiere, scene: &Scene) -> AnyLumiere {
+    let mut on = match light {
+        AnyLumiere::Off(l) => l.toggle(),
+        AnyLumiere::On(l) => l,
+        AnyLumiere::Dimmed(l) => l.into_on(),
+    };
+
+    if !scene.on {
+        return AnyLumiere::Off(on.toggle());
+    }
+    on.regulate_intensity(scene.intensity);
+    AnyLumiere::On(on)
+}
 
 
 fn main() {
@@ -193,11 +551,12 @@ fn main() {
     let l = l.toggle();
     println!("{:?}", l);
     let l = l.toggle();
-    println!("{:?}", l);
+    println!("{:?} is {:?}", l, l.state());
 }
 
 
 #[cfg(test)]
+#[allow(clippy::bool_assert_comparison)]
 mod test {
     use crate::*;
 
@@ -214,6 +573,151 @@ mod test {
         assert_eq!(light.is_on(), false);
     }
 
+    #[test]
+    fn light_chaining() {
+        let mut light: Light = Light::new();
+        light.toggle().regulate_intensity(50);
+        assert_eq!(light.is_on(), true);
+        assert_eq!(light.intensity, 50);
+
+        // The invariants still hold: off again means intensity 0, and regulating is ignored.
+        light.toggle().regulate_intensity(80);
+        assert_eq!(light.is_on(), false);
+        assert_eq!(light.intensity, 0);
+    }
+
+    #[test]
+    fn light_try_regulate_intensity() {
+        let mut light: Light = Light::new();
+        assert_eq!(light.try_regulate_intensity(50), Err(LightError::Off));
+        assert_eq!(light.intensity, 0);
+
+        light.toggle();
+        assert_eq!(light.try_regulate_intensity(MAX_INTENSITY + 1), Err(LightError::TooIntense(MAX_INTENSITY + 1)));
+        assert_eq!(light.intensity, 10);
+
+        assert_eq!(light.try_regulate_intensity(50), Ok(()));
+        assert_eq!(light.intensity, 50);
+    }
+
+    #[test]
+    fn light_fade_to() {
+        let mut light: Light = Light::new();
+        assert_eq!(light.fade_to(100, 3), Vec::<u8>::new());
+        assert_eq!(light.intensity, 0);
+
+        light.toggle();
+        assert_eq!(light.intensity, 10);
+        assert_eq!(light.fade_to(100, 3), vec![40, 70, 100]);
+        assert_eq!(light.intensity, 100);
+
+        assert_eq!(light.fade_to(50, 2), vec![75, 50]);
+        assert_eq!(light.intensity, 50);
+
+        assert_eq!(light.fade_to(80, 0), Vec::<u8>::new());
+        assert_eq!(light.intensity, 80);
+    }
+
+    #[test]
+    fn light_fade_to_many_steps() {
+        let mut light: Light = Light::new();
+        light.toggle();
+        light.fade_to(0, 1);
+
+        // 255 * 10_000_000 doesn't fit in an i32
+        let steps = 10_000_000;
+        let fade = light.fade_to(255, steps);
+        assert_eq!(fade.len(), steps);
+        assert!(fade.windows(2).all(|pair| pair[0] <= pair[1]));
+        assert_eq!(fade[steps / 2 - 1], 127);
+        assert_eq!(fade.last(), Some(&255));
+    }
+
+    #[test]
+    fn light_toggle_n() {
+        for n in [0, 1, 2, 7, 10] {
+            let mut expected: Light = Light::new();
+            let mut light: Light = Light::new();
+            // Start from an on light with a custom intensity, the interesting case.
+            expected.toggle();
+            expected.regulate_intensity(200);
+            light.toggle();
+            light.regulate_intensity(200);
+
+            for _ in 0..n {
+                expected.toggle();
+            }
+            light.toggle_n(n);
+            assert_eq!(light.is_on(), expected.is_on());
+            assert_eq!(light.intensity, expected.intensity);
+        }
+
+        let mut light: Light = Light::new();
+        light.toggle_n(1_000_001);
+        assert_eq!(light.is_on(), true);
+        assert_eq!(light.intensity, 10);
+        light.toggle_n(1_000_000);
+        assert_eq!(light.is_on(), true);
+    }
+
+    #[test]
+    fn pack_round_trip() {
+        let mut light: Light = Light::new();
+        assert_eq!(light.pack(), 0);
+        assert_eq!(Light::unpack(light.pack()), light);
+
+        light.toggle().regulate_intensity(75);
+        assert_eq!(light.pack(), 0x8000 | 75);
+        assert_eq!(Light::unpack(light.pack()), light);
+
+        light.regulate_intensity(255);
+        assert_eq!(Light::unpack(light.pack()), light);
+
+        // Off with an intensity is not a state a `Light` can be in
+        let off = Light::unpack(50);
+        assert_eq!(off.is_on(), false);
+        assert_eq!(off.intensity, 0);
+    }
+
+    #[test]
+    fn byte_round_trip() {
+        let mut light: Light = Light::new();
+        light.toggle().regulate_intensity(100);
+        assert_eq!(light.to_byte(), 0xFF);
+        let back = Light::from_byte(light.to_byte());
+        assert_eq!(back.is_on(), true);
+        assert_eq!(back.intensity, 100);
+
+        for intensity in 0..=MAX_INTENSITY {
+            light.regulate_intensity(intensity);
+            assert_eq!(Light::from_byte(light.to_byte()), light);
+        }
+
+        // Too intense is clamped, off is always 0
+        light.regulate_intensity(200);
+        assert_eq!(Light::from_byte(light.to_byte()).intensity, 100);
+        assert_eq!(Light::from_byte(light.toggle().to_byte()), Light::new());
+        assert_eq!(Light::from_byte(0x7F).intensity, 0);
+    }
+
+    #[test]
+    fn warmth() {
+        let mut light: Light = Light::new();
+        assert_eq!(light.warmth(), 0.0);
+
+        light.toggle();
+        let low = light.warmth();
+        assert!(low > 0.9);
+
+        light.regulate_intensity(MAX_INTENSITY);
+        let full = light.warmth();
+        assert!((full - 0.1).abs() < 1e-6);
+        assert!(full < low);
+
+        light.regulate_intensity(255);
+        assert_eq!(light.warmth(), full);
+    }
+
     #[test]
     fn luz_impl() {
         let luz = Luz::new();
@@ -224,6 +728,62 @@ mod test {
         assert_eq!(luz.is_on(), false);
     }
 
+    #[test]
+    fn luz_state_kind() {
+        let luz = Luz::new();
+        assert_eq!(luz.state(), LuzStateKind::Off);
+        let luz = luz.toggle();
+        assert_eq!(luz.state(), LuzStateKind::On);
+        let luz = luz.dim();
+        assert_eq!(luz.state(), LuzStateKind::Dimmed);
+        assert_eq!(luz.is_on(), true);
+        let luz = luz.toggle();
+        assert_eq!(luz.state(), LuzStateKind::Off);
+    }
+
+    #[test]
+    fn luz_metered() {
+        let mut luz = LuzMetered::new();
+        luz.tick(100.0);
+        assert_eq!(luz.energy_used(), 0.0);
+
+        let mut luz = luz.toggle();
+        luz.tick(1.5);
+        luz.tick(2.5);
+        assert_eq!(luz.energy_used(), 4.0 * LUZ_WATTS);
+
+        // The meter survives the transitions
+        let mut luz = luz.toggle();
+        luz.tick(10.0);
+        assert_eq!(luz.energy_used(), 4.0 * LUZ_WATTS);
+    }
+
+    #[test]
+    fn lumiere_intensity_label() {
+        let lumiere = Lumiere::new();
+        // lumiere.intensity_label(); // ERROR! An off light has no intensity to describe
+        let mut lumiere = lumiere.toggle();
+        for (intensity, label) in [(1, "Dim"), (20, "Dim"), (21, "Medium"), (60, "Medium"), (61, "Bright"), (100, "Bright")] {
+            lumiere.regulate_intensity(intensity);
+            assert_eq!(lumiere.intensity_label(), label);
+        }
+    }
+
+    #[test]
+    fn lumiere_fade_off() {
+        let mut lumiere = Lumiere::new().toggle();
+        lumiere.regulate_intensity(60);
+
+        let (fade, lumiere) = lumiere.fade_off(3);
+        assert_eq!(fade, vec![40, 20, 0]);
+        assert_eq!(lumiere.is_on(), false);
+        // lumiere.get_intensity(); // ERROR! It faded into `Lumiere<LumiereOff>`
+
+        let (fade, lumiere) = lumiere.toggle().fade_off(0);
+        assert!(fade.is_empty());
+        assert_eq!(lumiere.is_on(), false);
+    }
+
     #[test]
     fn lumiere_impl() {
         let lumiere = Lumiere::new();
@@ -236,4 +796,51 @@ mod test {
         let lumiere = lumiere.toggle();
         assert_eq!(lumiere.is_on(), false);
     }
+
+    #[test]
+    fn lumiere_would_be_on() {
+        let lumiere = Lumiere::new();
+        assert_eq!(lumiere.would_be_on(), !lumiere.is_on());
+        assert_eq!(lumiere.would_be_on(), true);
+        let lumiere = lumiere.toggle();
+        assert_eq!(lumiere.would_be_on(), !lumiere.is_on());
+        assert_eq!(lumiere.would_be_on(), false);
+    }
+
+    #[test]
+    fn lumiere_dimmed_keeps_intensity() {
+        let mut lumiere = Lumiere::new().toggle();
+        lumiere.regulate_intensity(80);
+        assert_eq!(lumiere.effective_intensity(), 80);
+
+        let lumiere = lumiere.into_dimmed();
+        assert_eq!(lumiere.is_on(), true);
+        assert_eq!(lumiere.effective_intensity(), 40);
+        assert_eq!(lumiere.get_intensity(), 80);
+
+        let lumiere = lumiere.into_on();
+        assert_eq!(lumiere.effective_intensity(), 80);
+    }
+
+    #[test]
+    fn lumiere_scenes() {
+        let light = apply_scene(AnyLumiere::Off(Lumiere::new()), &Scene::movie());
+        assert_eq!(light.is_on(), true);
+        assert_eq!(light.intensity(), Some(20));
+
+        let light = apply_scene(AnyLumiere::Off(Lumiere::new()), &Scene::reading());
+        assert_eq!(light.intensity(), Some(90));
+
+        // From any state
+        let light = apply_scene(light, &find_scene("movie").unwrap());
+        assert_eq!(light.intensity(), Some(20));
+        assert_eq!(find_scene("disco"), None);
+
+        // A name only known at runtime
+        let off = Scene::new(format!("{}-off", "bedroom"), false, 0);
+        assert_eq!(off.name, "bedroom-off");
+        let light = apply_scene(light, &off);
+        assert_eq!(light.is_on(), false);
+        assert_eq!(light.intensity(), None);
+    }
 }
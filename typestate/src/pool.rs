@@ -0,0 +1,83 @@
+// A connection pool has a lifecycle: first it gets a size, then it connects, and only then it can
+// hand out connections. Each step is a type, so skipping one does not compile.
+
+pub trait PoolState {}
+
+#[derive(Debug)]
+pub struct Uninitialized;
+
+#[derive(Debug)]
+pub struct Configured {
+    size: usize,
+}
+
+#[derive(Debug)]
+pub struct Open {
+    size: usize,
+    in_use: usize,
+}
+
+impl PoolState for Uninitialized {}
+impl PoolState for Configured {}
+impl PoolState for Open {}
+
+#[derive(Debug, PartialEq)]
+pub struct Connection {
+    id: usize,
+}
+
+#[derive(Debug)]
+pub struct Pool<S: PoolState> {
+    state: S,
+}
+
+#[allow(dead_code)]
+impl Pool<Uninitialized> {
+    pub fn new() -> Pool<Uninitialized> {
+        Pool { state: Uninitialized }
+    }
+
+    pub fn with_size(self, size: usize) -> Pool<Configured> {
+        Pool { state: Configured { size } }
+    }
+}
+
+#[allow(dead_code)]
+impl Pool<Configured> {
+    pub fn connect(self) -> Pool<Open> {
+        Pool { state: Open { size: self.state.size, in_use: 0 } }
+    }
+}
+
+#[allow(dead_code)]
+impl Pool<Open> {
+    /// Runs out once `size` connections were handed out.
+    pub fn acquire(&mut self) -> Option<Connection> {
+        if self.state.in_use == self.state.size {
+            return None;
+        }
+        self.state.in_use += 1;
+        Some(Connection { id: self.state.in_use })
+    }
+
+    pub fn available(&self) -> usize {
+        self.state.size - self.state.in_use
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::pool::{Connection, Pool};
+
+    #[test]
+    fn pool_lifecycle() {
+        let pool = Pool::new().with_size(2);
+        // pool.acquire(); // ERROR! There is no `acquire` before `connect`
+        let mut pool = pool.connect();
+        assert_eq!(pool.available(), 2);
+        assert_eq!(pool.acquire(), Some(Connection { id: 1 }));
+        assert_eq!(pool.acquire(), Some(Connection { id: 2 }));
+        assert_eq!(pool.available(), 0);
+        assert_eq!(pool.acquire(), None);
+    }
+}
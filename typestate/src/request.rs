@@ -0,0 +1,83 @@
+// The same idea as the lights, but in a domain closer to real life: an HTTP request.
+// A request without an URL can't be sent. Instead of checking it at runtime, the `send` method
+// just does not exist until an URL was given.
+
+pub trait RequestState {}
+
+#[derive(Debug)]
+pub struct NoUrl;
+
+#[derive(Debug)]
+pub struct HasUrl {
+    url: String,
+}
+
+#[derive(Debug)]
+pub struct HasBody {
+    url: String,
+    body: String,
+}
+
+impl RequestState for NoUrl {}
+impl RequestState for HasUrl {}
+impl RequestState for HasBody {}
+
+/// Like `Lumiere`, each state carries the data it has collected so far.
+#[derive(Debug)]
+pub struct Request<S: RequestState> {
+    state: S,
+}
+
+#[allow(dead_code)]
+impl Request<NoUrl> {
+    pub fn new() -> Request<NoUrl> {
+        Request { state: NoUrl }
+    }
+
+    pub fn url(self, url: impl Into<String>) -> Request<HasUrl> {
+        Request { state: HasUrl { url: url.into() } }
+    }
+}
+
+#[allow(dead_code)]
+impl Request<HasUrl> {
+    pub fn body(self, body: impl Into<String>) -> Request<HasBody> {
+        Request {
+            state: HasBody { url: self.state.url, body: body.into() },
+        }
+    }
+
+    /// Without a body it's a `GET`.
+    pub fn send(self) -> String {
+        format!("GET {}", self.state.url)
+    }
+}
+
+#[allow(dead_code)]
+impl Request<HasBody> {
+    /// With a body it's a `POST`.
+    pub fn send(self) -> String {
+        format!("POST {}\n\n{}", self.state.url, self.state.body)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::request::Request;
+
+    #[test]
+    fn get_request() {
+        let request = Request::new();
+        // request.send(); // ERROR! There is no `send` on `Request<NoUrl>`
+        let request = request.url("https://example.com");
+        assert_eq!(request.send(), "GET https://example.com");
+    }
+
+    #[test]
+    fn post_request() {
+        let request = Request::new()
+            .url("https://example.com/lights")
+            .body("{\"on\": true}");
+        assert_eq!(request.send(), "POST https://example.com/lights\n\n{\"on\": true}");
+    }
+}
@@ -0,0 +1,134 @@
+use std::num::NonZeroU32;
+use std::time::Duration;
+
+// Retrying a request: send it, and if it fails either try again or give up. The attempt count is
+// a value, the typestate only decides what can be done with it: a `Failed` request can't be sent
+// before deciding to `retry`, and an `Exhausted` one can't be sent at all.
+
+pub trait RetryState {}
+
+#[derive(Debug)]
+pub struct Ready {
+    attempts: u32,
+    max_attempts: u32,
+}
+
+#[derive(Debug)]
+pub struct Failed {
+    attempts: u32,
+    max_attempts: u32,
+}
+
+#[derive(Debug)]
+pub struct Exhausted {
+    attempts: u32,
+}
+
+impl RetryState for Ready {}
+impl RetryState for Failed {}
+impl RetryState for Exhausted {}
+
+#[derive(Debug)]
+pub struct Retryable<S: RetryState> {
+    state: S,
+}
+
+/// The first retry waits this long, every next one twice as much.
+const BASE_BACKOFF: Duration = Duration::from_millis(100);
+
+#[allow(dead_code)]
+impl Retryable<Ready> {
+    /// Up to `max_attempts` sends in total, the first one included. A request that can't be sent
+    /// even once makes no sense, so zero attempts doesn't type check.
+    pub fn new(max_attempts: NonZeroU32) -> Retryable<Ready> {
+        Retryable { state: Ready { attempts: 0, max_attempts: max_attempts.get() } }
+    }
+
+    /// Runs `request`. On failure the error comes back together with the `Failed` request, to
+    /// decide what to do next.
+    pub fn send<T, E>(self, request: impl FnOnce() -> Result<T, E>) -> Result<T, (E, Retryable<Failed>)> {
+        let Ready { attempts, max_attempts } = self.state;
+        request().map_err(|err| (err, Retryable { state: Failed { attempts: attempts + 1, max_attempts } }))
+    }
+}
+
+#[allow(dead_code)]
+impl Retryable<Failed> {
+    pub fn attempts(&self) -> u32 {
+        self.state.attempts
+    }
+
+    /// How long to wait before the next attempt: exponential backoff, saturating at
+    /// `Duration::MAX` instead of overflowing.
+    pub fn backoff(&self) -> Duration {
+        BASE_BACKOFF.checked_mul(2u32.saturating_pow(self.state.attempts - 1)).unwrap_or(Duration::MAX)
+    }
+
+    /// Back to `Ready` while there are attempts left, `Exhausted` otherwise.
+    pub fn retry(self) -> Result<Retryable<Ready>, Retryable<Exhausted>> {
+        let Failed { attempts, max_attempts } = self.state;
+        if attempts < max_attempts {
+            Ok(Retryable { state: Ready { attempts, max_attempts } })
+        } else {
+            Err(Retryable { state: Exhausted { attempts } })
+        }
+    }
+}
+
+#[allow(dead_code)]
+impl Retryable<Exhausted> {
+    pub fn attempts(&self) -> u32 {
+        self.state.attempts
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::num::NonZeroU32;
+    use std::time::Duration;
+
+    use crate::retryable::Retryable;
+
+    const THREE: NonZeroU32 = NonZeroU32::new(3).unwrap();
+
+    #[test]
+    fn first_attempt_succeeds() {
+        let response: Result<&str, (&str, _)> = Retryable::new(THREE).send(|| Ok("200 OK"));
+        assert_eq!(response.unwrap(), "200 OK");
+    }
+
+    #[test]
+    fn fails_until_exhausted() {
+        let mut request = Retryable::new(THREE);
+        let mut backoffs = Vec::new();
+        let exhausted = loop {
+            let (err, failed) = request.send(|| Err::<(), _>("timeout")).unwrap_err();
+            assert_eq!(err, "timeout");
+            // failed.send(|| Ok(())); // ERROR! There is no `send` on `Retryable<Failed>`
+            backoffs.push(failed.backoff());
+            match failed.retry() {
+                Ok(ready) => request = ready,
+                Err(exhausted) => break exhausted,
+            }
+        };
+
+        assert_eq!(exhausted.attempts(), 3);
+        assert_eq!(backoffs, [100, 200, 400].map(Duration::from_millis));
+    }
+
+    #[test]
+    fn succeeds_on_a_retry() {
+        let (_, failed) = Retryable::new(THREE).send(|| Err::<u32, _>("timeout")).unwrap_err();
+        assert_eq!(failed.attempts(), 1);
+        let ready = failed.retry().unwrap();
+        assert_eq!(ready.send(|| Ok::<_, &str>(42)).unwrap(), 42);
+    }
+
+    #[test]
+    fn one_attempt_means_no_retry() {
+        // Retryable::new(0); // ERROR! expected `NonZeroU32`, found integer
+        let (_, failed) = Retryable::new(NonZeroU32::MIN).send(|| Err::<(), _>("timeout")).unwrap_err();
+        assert_eq!(failed.attempts(), 1);
+        assert_eq!(failed.retry().unwrap_err().attempts(), 1);
+    }
+}
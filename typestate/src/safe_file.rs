@@ -0,0 +1,78 @@
+use std::fs::File;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+// A file has to be opened before it can be written. `std::fs::File` solves it by only giving you
+// a `File` once it's open, but what about closing it? Here both ends are states: there is no
+// `write` on a closed file, and closing consumes the open one so it can't be used afterwards.
+
+pub trait FileState {}
+
+#[derive(Debug)]
+pub struct Closed;
+
+#[derive(Debug)]
+pub struct Open {
+    file: File,
+    path: PathBuf,
+}
+
+impl FileState for Closed {}
+impl FileState for Open {}
+
+#[derive(Debug)]
+pub struct SafeFile<S: FileState> {
+    state: S,
+}
+
+#[allow(dead_code)]
+impl SafeFile<Closed> {
+    pub fn new() -> SafeFile<Closed> {
+        SafeFile { state: Closed }
+    }
+
+    /// Creates the file, or truncates it if it already exists. Opening can fail, so the
+    /// transition is wrapped in an `io::Result`.
+    pub fn open(self, path: impl AsRef<Path>) -> io::Result<SafeFile<Open>> {
+        let path = path.as_ref().to_path_buf();
+        let file = File::create(&path)?;
+        Ok(SafeFile { state: Open { file, path } })
+    }
+}
+
+#[allow(dead_code)]
+impl SafeFile<Open> {
+    pub fn write(&mut self, data: &[u8]) -> io::Result<()> {
+        self.state.file.write_all(data)
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.state.path
+    }
+
+    /// Dropping the `File` closes it. `File` doesn't buffer, everything written is already out.
+    pub fn close(self) -> SafeFile<Closed> {
+        SafeFile { state: Closed }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::safe_file::SafeFile;
+
+    #[test]
+    fn open_write_close() -> std::io::Result<()> {
+        let path = std::env::temp_dir().join(format!("typestate-safe-file-{}.txt", std::process::id()));
+
+        let file = SafeFile::new();
+        // file.write(b"hello"); // ERROR! There is no `write` on `SafeFile<Closed>`
+        let mut file = file.open(&path)?;
+        file.write(b"hello ")?;
+        file.write(b"world")?;
+        let _file = file.close();
+        // _file.write(b"again"); // ERROR! Closed again, no `write`
+
+        assert_eq!(std::fs::read_to_string(&path)?, "hello world");
+        std::fs::remove_file(&path)
+    }
+}
@@ -0,0 +1,99 @@
+use std::time::Duration;
+
+// A traffic light only goes one way: Red, Green, Yellow and back to Red. Instead of writing a
+// `next` for every state, each state names the one after it with an associated type, and a single
+// generic `next` follows it. Asking a Red light to turn Yellow is not something you can even write.
+
+pub trait TrafficState {
+    type NextState: TrafficState;
+    /// How long the light stays in this state.
+    const DURATION: Duration;
+
+    fn advance(self) -> Self::NextState;
+}
+
+#[derive(Debug)]
+pub struct Red;
+
+#[derive(Debug)]
+pub struct Green;
+
+#[derive(Debug)]
+pub struct Yellow;
+
+impl TrafficState for Red {
+    type NextState = Green;
+    const DURATION: Duration = Duration::from_secs(30);
+
+    fn advance(self) -> Green { Green }
+}
+
+impl TrafficState for Green {
+    type NextState = Yellow;
+    const DURATION: Duration = Duration::from_secs(25);
+
+    fn advance(self) -> Yellow { Yellow }
+}
+
+impl TrafficState for Yellow {
+    type NextState = Red;
+    const DURATION: Duration = Duration::from_secs(5);
+
+    fn advance(self) -> Red { Red }
+}
+
+#[derive(Debug)]
+pub struct TrafficLight<S: TrafficState> {
+    state: S,
+}
+
+#[allow(dead_code)]
+impl TrafficLight<Red> {
+    /// Every traffic light starts on red, the safe state.
+    pub fn new() -> TrafficLight<Red> {
+        TrafficLight { state: Red }
+    }
+}
+
+#[allow(dead_code)]
+impl<S: TrafficState> TrafficLight<S> {
+    pub fn next(self) -> TrafficLight<S::NextState> {
+        TrafficLight { state: self.state.advance() }
+    }
+
+    pub fn duration(&self) -> Duration {
+        S::DURATION
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use crate::traffic_light::{Red, TrafficLight};
+
+    #[test]
+    fn full_cycle() {
+        let red = TrafficLight::new();
+        assert_eq!(red.duration(), Duration::from_secs(30));
+        let green = red.next();
+        assert_eq!(green.duration(), Duration::from_secs(25));
+        let yellow = green.next();
+        assert_eq!(yellow.duration(), Duration::from_secs(5));
+        // let red = green.next(); // ERROR! `green` was moved, there is no going back to it
+
+        let red: TrafficLight<Red> = yellow.next();
+        assert_eq!(red.duration(), Duration::from_secs(30));
+    }
+
+    #[test]
+    fn cycle_length() {
+        let light = TrafficLight::new();
+        let cycle = light.duration();
+        let light = light.next();
+        let cycle = cycle + light.duration();
+        let light = light.next();
+        let cycle = cycle + light.duration();
+        assert_eq!(cycle, Duration::from_secs(60));
+    }
+}
@@ -0,0 +1,92 @@
+// The classic database transaction: statements run between `BEGIN` and either `COMMIT` or
+// `ROLLBACK`. Both ends consume the transaction, and neither terminal state has an `execute`, so
+// running a statement after the transaction ended doesn't compile.
+
+pub trait TransactionState {}
+
+#[derive(Debug)]
+pub struct Begun {
+    statements: Vec<String>,
+}
+
+/// The statements that made it to the database.
+#[derive(Debug)]
+pub struct Committed {
+    statements: Vec<String>,
+}
+
+/// How many statements were thrown away.
+#[derive(Debug)]
+pub struct RolledBack {
+    discarded: usize,
+}
+
+impl TransactionState for Begun {}
+impl TransactionState for Committed {}
+impl TransactionState for RolledBack {}
+
+#[derive(Debug)]
+pub struct Transaction<S: TransactionState> {
+    state: S,
+}
+
+#[allow(dead_code)]
+impl Transaction<Begun> {
+    pub fn begin() -> Transaction<Begun> {
+        Transaction { state: Begun { statements: Vec::new() } }
+    }
+
+    pub fn execute(&mut self, stmt: &str) -> &mut Self {
+        self.state.statements.push(stmt.to_string());
+        self
+    }
+
+    pub fn commit(self) -> Transaction<Committed> {
+        Transaction { state: Committed { statements: self.state.statements } }
+    }
+
+    pub fn rollback(self) -> Transaction<RolledBack> {
+        Transaction { state: RolledBack { discarded: self.state.statements.len() } }
+    }
+}
+
+#[allow(dead_code)]
+impl Transaction<Committed> {
+    pub fn statements(&self) -> &[String] {
+        &self.state.statements
+    }
+}
+
+#[allow(dead_code)]
+impl Transaction<RolledBack> {
+    pub fn discarded(&self) -> usize {
+        self.state.discarded
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::transaction::Transaction;
+
+    #[test]
+    fn execute_then_commit() {
+        let mut tx = Transaction::begin();
+        tx.execute("INSERT INTO lights VALUES (1, 'on')")
+            .execute("UPDATE lights SET intensity = 50 WHERE id = 1");
+        let tx = tx.commit();
+        // tx.execute("DELETE FROM lights"); // ERROR! There is no `execute` on `Transaction<Committed>`
+        // tx.rollback(); // ERROR! A committed transaction can't be rolled back
+
+        assert_eq!(tx.statements(), ["INSERT INTO lights VALUES (1, 'on')", "UPDATE lights SET intensity = 50 WHERE id = 1"]);
+    }
+
+    #[test]
+    fn rollback_discards() {
+        let mut tx = Transaction::begin();
+        tx.execute("DELETE FROM lights");
+        let tx = tx.rollback();
+        // tx.execute("DELETE FROM lights"); // ERROR! Nor on `Transaction<RolledBack>`
+
+        assert_eq!(tx.discarded(), 1);
+    }
+}
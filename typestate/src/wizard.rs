@@ -0,0 +1,118 @@
+// A sign-up form in two steps: first the name, then the email, then it can be finished. The
+// wizard can't skip a step, and every step only accepts data that was already validated: a
+// `Name` or an `Email` can only be created through their `parse`, which checks them.
+
+pub trait WizardState {}
+
+#[derive(Debug)]
+pub struct StepOne;
+
+#[derive(Debug)]
+pub struct StepTwo {
+    name: Name,
+}
+
+#[derive(Debug)]
+pub struct Complete {
+    name: Name,
+    email: Email,
+}
+
+impl WizardState for StepOne {}
+impl WizardState for StepTwo {}
+impl WizardState for Complete {}
+
+/// A name that is not blank.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Name(String);
+
+/// An email with something on both sides of the `@`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Email(String);
+
+#[allow(dead_code)]
+impl Name {
+    pub fn parse(s: &str) -> Option<Name> {
+        let s = s.trim();
+        (!s.is_empty()).then(|| Name(s.to_string()))
+    }
+}
+
+#[allow(dead_code)]
+impl Email {
+    pub fn parse(s: &str) -> Option<Email> {
+        match s.trim().split_once('@') {
+            Some((user, domain)) if !user.is_empty() && !domain.is_empty() => Some(Email(s.trim().to_string())),
+            _ => None,
+        }
+    }
+}
+
+/// What the wizard produces once it's done.
+#[derive(Debug, PartialEq)]
+pub struct Registration {
+    pub name: String,
+    pub email: String,
+}
+
+#[derive(Debug)]
+pub struct Wizard<S: WizardState> {
+    state: S,
+}
+
+#[allow(dead_code)]
+impl Wizard<StepOne> {
+    pub fn new() -> Wizard<StepOne> {
+        Wizard { state: StepOne }
+    }
+
+    pub fn name(self, name: Name) -> Wizard<StepTwo> {
+        Wizard { state: StepTwo { name } }
+    }
+}
+
+#[allow(dead_code)]
+impl Wizard<StepTwo> {
+    pub fn email(self, email: Email) -> Wizard<Complete> {
+        Wizard { state: Complete { name: self.state.name, email } }
+    }
+
+    /// Going back keeps nothing, the name has to be given again.
+    pub fn back(self) -> Wizard<StepOne> {
+        Wizard { state: StepOne }
+    }
+}
+
+#[allow(dead_code)]
+impl Wizard<Complete> {
+    pub fn finish(self) -> Registration {
+        Registration { name: self.state.name.0, email: self.state.email.0 }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::wizard::{Email, Name, Registration, Wizard};
+
+    #[test]
+    fn all_steps() {
+        let wizard = Wizard::new();
+        // wizard.finish(); // ERROR! There is no `finish` on `Wizard<StepOne>`
+        // wizard.email(Email::parse("hector@example.com").unwrap()); // ERROR! The name comes first
+        let wizard = wizard.name(Name::parse(" Hector ").unwrap());
+        let wizard = wizard.email(Email::parse("hector@example.com").unwrap());
+
+        assert_eq!(
+            wizard.finish(),
+            Registration { name: String::from("Hector"), email: String::from("hector@example.com") }
+        );
+    }
+
+    #[test]
+    fn invalid_data_never_reaches_a_step() {
+        // wizard.name("   "); // ERROR! A step wants a `Name`, not any `&str`
+        assert_eq!(Name::parse("   "), None);
+        assert_eq!(Email::parse("hector"), None);
+        assert_eq!(Email::parse("@example.com"), None);
+    }
+}